@@ -77,10 +77,12 @@ pub(crate) enum DecodedOpcode {
 }
 
 type MapItem = (&'static str, u8);
-pub(super) fn opcode_mapping() -> [MapItem; 52] {
+pub(super) fn opcode_mapping() -> [MapItem; 53] {
     [
         // opcode 1 is unused
-        // opcode 2 may be XEQ, but no documentation on this.
+        // opcode 2 may be XEQ, but no documentation on this.  The
+        // emulator executes it only if XEQ support is enabled.
+        ("XEQ", 0o2),
         // opcode 3 is unused
         ("IOS", 0o4), // see also Vol 3 page 16-05-07
         ("JMP", 0o5),
@@ -590,6 +590,7 @@ where
     I: Input<'a, Token = char, Span = SimpleSpan> + ValueInput<'a> + Clone,
 {
     let group0 = choice((
+        just("XEQ").to(u6!(0o2)),
         just("IOS").to(u6!(0o4)),
         just("JMP").to(u6!(0o5)),
         just("JPX").to(u6!(0o6)),
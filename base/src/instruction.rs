@@ -230,7 +230,12 @@ impl Inst for Instruction {
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
 pub enum Opcode {
     // opcode 1 is unused
-    // opcode 2 may be XEQ, but no documentation on this.
+    /// Opcode 2 may be XEQ ("execute"), which appears as a
+    /// hand-written note on the opcode table of a copy of the User
+    /// Handbook, but is not otherwise documented.  The emulator only
+    /// executes it when experimental XEQ support is enabled (see
+    /// OPEN-QUESTIONS.md).
+    Xeq = 0o2,
     // opcode 3 is unused
     Ios = 0o4, // see also Vol 3 page 16-05-07
     Jmp = 0o5,
@@ -322,10 +327,7 @@ impl TryFrom<u8> for Opcode {
         match opcode {
             // TODO: change these opcode values to octal.
             0 | 1 => Err(DisassemblyFailure::InvalidOpcode(opcode)),
-            2 => {
-                // Maybe XEQ?
-                Err(DisassemblyFailure::UnimplementedOpcode(opcode))
-            }
+            2 => Ok(Xeq), // Maybe XEQ, see OPEN-QUESTIONS.md.
             3 => Err(DisassemblyFailure::InvalidOpcode(opcode)),
             0o4 => Ok(Ios),
             0o5 => Ok(Jmp),
@@ -488,9 +490,10 @@ pub enum DisassemblyFailure {
 
     /// The opcode field of the instruction word corresponds to an
     /// operation we know nothing about.  This enumerator shouldn't be
-    /// considered stable; we might remove it entirely.  Currently only
-    /// opcode 2 yields this result (corresponding to "XEQ?"
-    /// hand-written on a copy of the User Handbook).
+    /// considered stable; we might remove it entirely.  Opcode 2
+    /// (corresponding to "XEQ?" hand-written on a copy of the User
+    /// Handbook) used to yield this result, but is now decoded as
+    /// [`Opcode::Xeq`], so currently no opcode does.
     UnimplementedOpcode(u8),
 }
 
@@ -46,6 +46,7 @@ impl Display for Opcode {
     fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
         use Opcode::*;
         f.write_str(match self {
+            Xeq => "XEQ",
             Ios => "IOS",
             Jmp => "JMP",
             Jpx => "JPX",
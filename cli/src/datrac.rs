@@ -0,0 +1,219 @@
+use std::ffi::OsStr;
+use std::fmt::{self, Display, Formatter};
+use std::path::Path;
+
+/// Samples to be fed to the DATRAC A/D converter (unit 50).
+#[derive(Debug)]
+pub struct SampleFile {
+    pub samples: Vec<i16>,
+    /// The sample rate recorded in the file (CSV files don't
+    /// include one).
+    pub sample_rate: Option<u32>,
+}
+
+#[derive(Debug)]
+pub struct BadSampleFile(String);
+
+impl Display for BadSampleFile {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(f, "bad DATRAC sample file: {}", self.0)
+    }
+}
+
+impl std::error::Error for BadSampleFile {}
+
+/// Read a sample file.  Files whose names end in `.wav` are read as
+/// WAV files (only 8-bit and 16-bit PCM are supported, and only the
+/// first channel is used).  Other files are read as CSV, using the
+/// first field of each line as a sample value.
+pub fn read_sample_file(file_name: &OsStr) -> Result<SampleFile, Box<dyn std::error::Error>> {
+    let data: Vec<u8> = std::fs::read(file_name)?;
+    let is_wav = Path::new(file_name)
+        .extension()
+        .map(|ext| ext.eq_ignore_ascii_case("wav"))
+        .unwrap_or(false);
+    if is_wav {
+        Ok(parse_wav(&data)?)
+    } else {
+        Ok(parse_csv(&String::from_utf8_lossy(&data))?)
+    }
+}
+
+fn parse_csv(text: &str) -> Result<SampleFile, BadSampleFile> {
+    let mut samples: Vec<i16> = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let field = line.split(',').next().unwrap_or("").trim();
+        match field.parse::<i16>() {
+            Ok(sample) => samples.push(sample),
+            Err(e) => {
+                return Err(BadSampleFile(format!(
+                    "line {}: bad sample '{}': {}",
+                    index + 1,
+                    field,
+                    e
+                )));
+            }
+        }
+    }
+    Ok(SampleFile {
+        samples,
+        sample_rate: None,
+    })
+}
+
+fn le_u16(data: &[u8], pos: usize) -> Option<u16> {
+    data.get(pos..pos.saturating_add(2))
+        .map(|b| u16::from_le_bytes([b[0], b[1]]))
+}
+
+fn le_u32(data: &[u8], pos: usize) -> Option<u32> {
+    data.get(pos..pos.saturating_add(4))
+        .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+fn parse_wav(data: &[u8]) -> Result<SampleFile, BadSampleFile> {
+    let fail = |message: &str| BadSampleFile(message.to_string());
+    if data.get(0..4) != Some(b"RIFF") || data.get(8..12) != Some(b"WAVE") {
+        return Err(fail("not a WAV file"));
+    }
+    // (channels, sample rate, bits per sample)
+    let mut format: Option<(u16, u32, u16)> = None;
+    let mut pos: usize = 12;
+    while let (Some(id), Some(len)) = (
+        data.get(pos..pos.saturating_add(4)),
+        le_u32(data, pos.saturating_add(4)),
+    ) {
+        let len = usize::try_from(len).unwrap_or(usize::MAX);
+        let body_start = pos + 8;
+        let body_end = body_start.saturating_add(len).min(data.len());
+        match id {
+            b"fmt " => {
+                let audio_format = le_u16(data, body_start);
+                let channels = le_u16(data, body_start + 2);
+                let sample_rate = le_u32(data, body_start + 4);
+                let bits = le_u16(data, body_start + 14);
+                match (audio_format, channels, sample_rate, bits) {
+                    (Some(1), Some(channels), Some(rate), Some(bits @ (8 | 16)))
+                        if channels > 0 =>
+                    {
+                        format = Some((channels, rate, bits));
+                    }
+                    _ => {
+                        return Err(fail("only 8-bit and 16-bit PCM WAV files are supported"));
+                    }
+                }
+            }
+            b"data" => {
+                let (channels, sample_rate, bits) =
+                    format.ok_or_else(|| fail("the data chunk precedes the fmt chunk"))?;
+                let bytes_per_sample = usize::from(bits / 8);
+                let frame_size = bytes_per_sample * usize::from(channels);
+                let samples: Vec<i16> = data[body_start..body_end]
+                    .chunks_exact(frame_size)
+                    .map(|frame| match bits {
+                        // 8-bit WAV samples are unsigned.
+                        8 => (i16::from(frame[0]) - 128) << 8,
+                        _ => i16::from_le_bytes([frame[0], frame[1]]),
+                    })
+                    .collect();
+                return Ok(SampleFile {
+                    samples,
+                    sample_rate: Some(sample_rate),
+                });
+            }
+            _ => (),
+        }
+        // Chunks are padded to an even length.
+        pos = body_start.saturating_add(len).saturating_add(len % 2);
+    }
+    Err(fail("the file has no data chunk"))
+}
+
+#[cfg(test)]
+fn make_wav(chunks: &[(&[u8; 4], Vec<u8>)]) -> Vec<u8> {
+    let mut body: Vec<u8> = b"WAVE".to_vec();
+    for (id, data) in chunks {
+        body.extend_from_slice(*id);
+        let len = u32::try_from(data.len()).expect("test chunk is small");
+        body.extend_from_slice(&len.to_le_bytes());
+        body.extend_from_slice(data);
+        if data.len() % 2 == 1 {
+            body.push(0);
+        }
+    }
+    let mut result: Vec<u8> = b"RIFF".to_vec();
+    let len = u32::try_from(body.len()).expect("test file is small");
+    result.extend_from_slice(&len.to_le_bytes());
+    result.extend(body);
+    result
+}
+
+#[cfg(test)]
+fn make_fmt_chunk(channels: u16, sample_rate: u32, bits: u16) -> Vec<u8> {
+    let block_align = channels * (bits / 8);
+    let mut result: Vec<u8> = Vec::new();
+    result.extend_from_slice(&1_u16.to_le_bytes()); // PCM
+    result.extend_from_slice(&channels.to_le_bytes());
+    result.extend_from_slice(&sample_rate.to_le_bytes());
+    result.extend_from_slice(&(sample_rate * u32::from(block_align)).to_le_bytes());
+    result.extend_from_slice(&block_align.to_le_bytes());
+    result.extend_from_slice(&bits.to_le_bytes());
+    result
+}
+
+#[test]
+fn test_parse_wav() {
+    // 16-bit mono.
+    let data: Vec<u8> = [1_i16, -2, 300]
+        .iter()
+        .flat_map(|sample| sample.to_le_bytes())
+        .collect();
+    let wav = make_wav(&[(b"fmt ", make_fmt_chunk(1, 8000, 16)), (b"data", data)]);
+    let parsed = parse_wav(&wav).expect("16-bit mono WAV should be valid");
+    assert_eq!(parsed.samples, vec![1, -2, 300]);
+    assert_eq!(parsed.sample_rate, Some(8000));
+
+    // 8-bit stereo; only the first channel is used, and 8-bit
+    // samples are unsigned.
+    let wav = make_wav(&[
+        (b"fmt ", make_fmt_chunk(2, 11025, 8)),
+        (b"data", vec![128, 0, 255, 7, 0, 9]),
+    ]);
+    let parsed = parse_wav(&wav).expect("8-bit stereo WAV should be valid");
+    assert_eq!(parsed.samples, vec![0, 127 << 8, -128 << 8]);
+    assert_eq!(parsed.sample_rate, Some(11025));
+
+    // An odd-length chunk before the data chunk is padded.
+    let wav = make_wav(&[
+        (b"fmt ", make_fmt_chunk(1, 8000, 16)),
+        (b"LIST", vec![1, 2, 3]),
+        (b"data", 5_i16.to_le_bytes().to_vec()),
+    ]);
+    let parsed = parse_wav(&wav).expect("WAV with odd-length chunk should be valid");
+    assert_eq!(parsed.samples, vec![5]);
+
+    // The data chunk can't be understood without a fmt chunk.
+    let wav = make_wav(&[(b"data", 5_i16.to_le_bytes().to_vec())]);
+    assert!(parse_wav(&wav).is_err());
+
+    assert!(parse_wav(b"this is not a WAV file").is_err());
+}
+
+#[test]
+fn test_parse_csv() {
+    let parsed = parse_csv("# time series\n10,0.0\n\n  -20 , 0.1\n30\n")
+        .expect("CSV with comments should be valid");
+    assert_eq!(parsed.samples, vec![10, -20, 30]);
+    assert_eq!(parsed.sample_rate, None);
+
+    match parse_csv("1\nfish\n3\n") {
+        Err(e) => assert!(e.to_string().contains("line 2")),
+        Ok(parsed) => panic!("bad CSV line was accepted: {parsed:?}"),
+    }
+    // Samples must fit in 16 bits.
+    assert!(parse_csv("40000\n").is_err());
+}
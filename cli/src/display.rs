@@ -0,0 +1,113 @@
+use std::ffi::OsString;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+use std::time::Duration;
+
+use clap::ValueEnum;
+use tracing::{event, Level};
+
+use cpu::{PhosphorFramebuffer, DISPLAY_SIZE};
+
+/// File format for dumped display frames.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum FrameFormat {
+    Ppm,
+    Png,
+}
+
+impl FrameFormat {
+    fn extension(&self) -> &'static str {
+        match self {
+            FrameFormat::Ppm => "ppm",
+            FrameFormat::Png => "png",
+        }
+    }
+}
+
+/// Collects the points plotted on the display (unit 60) and
+/// periodically writes the contents of the screen to a file.
+pub struct FrameDumper {
+    screen: PhosphorFramebuffer,
+    dir: PathBuf,
+    format: FrameFormat,
+    interval: Duration,
+    next_frame_due: Option<Duration>,
+    frames_written: usize,
+}
+
+impl FrameDumper {
+    pub fn new(dir: OsString, format: FrameFormat, interval: Duration) -> FrameDumper {
+        FrameDumper {
+            screen: PhosphorFramebuffer::new(),
+            dir: PathBuf::from(dir),
+            format,
+            interval,
+            next_frame_due: None,
+            frames_written: 0,
+        }
+    }
+
+    pub fn plot(&mut self, x: u16, y: u16, intensity: u8, when: Duration) {
+        self.screen.plot(x, y, intensity, when);
+    }
+
+    /// Write a frame if one is due at simulated time `now`.  The
+    /// first frame is due one interval after the first call.
+    pub fn maybe_write_frame(&mut self, now: Duration) -> Result<(), std::io::Error> {
+        match self.next_frame_due {
+            None => {
+                self.next_frame_due = Some(now + self.interval);
+                Ok(())
+            }
+            Some(due) if due <= now => {
+                self.next_frame_due = Some(now + self.interval);
+                self.write_frame(now)
+            }
+            Some(_) => Ok(()),
+        }
+    }
+
+    /// Write the contents of the screen as seen at simulated time
+    /// `now`.
+    pub fn write_frame(&mut self, now: Duration) -> Result<(), std::io::Error> {
+        self.screen.prune(now);
+        let pixels = self.screen.render(now);
+        self.frames_written += 1;
+        let path = self.dir.join(format!(
+            "frame-{:06}.{}",
+            self.frames_written,
+            self.format.extension()
+        ));
+        event!(
+            Level::DEBUG,
+            "writing display frame for time {:?} to {}",
+            now,
+            path.display()
+        );
+        let mut w = BufWriter::new(File::create(&path)?);
+        match self.format {
+            FrameFormat::Ppm => write_ppm(&mut w, &pixels)?,
+            FrameFormat::Png => write_png(&mut w, &pixels)?,
+        }
+        w.flush()
+    }
+}
+
+fn write_ppm<W: Write>(w: &mut W, pixels: &[u8]) -> Result<(), std::io::Error> {
+    write!(w, "P6\n{} {}\n255\n", DISPLAY_SIZE, DISPLAY_SIZE)?;
+    for level in pixels {
+        w.write_all(&[*level, *level, *level])?;
+    }
+    Ok(())
+}
+
+fn write_png<W: Write>(w: &mut W, pixels: &[u8]) -> Result<(), std::io::Error> {
+    let mut encoder = png::Encoder::new(w, DISPLAY_SIZE.into(), DISPLAY_SIZE.into());
+    encoder.set_color(png::ColorType::Grayscale);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header()?;
+    writer.write_image_data(pixels)?;
+    writer.finish()?;
+    Ok(())
+}
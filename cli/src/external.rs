@@ -0,0 +1,168 @@
+use std::collections::VecDeque;
+use std::fmt::{self, Display, Formatter};
+use std::time::Duration;
+
+use base::prelude::*;
+
+#[derive(Debug)]
+pub struct BadExternalInput {
+    /// Where the bad entry came from (for example, a line number).
+    location: String,
+    message: String,
+}
+
+impl Display for BadExternalInput {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(
+            f,
+            "external input register values, {}: {}",
+            self.location, self.message
+        )
+    }
+}
+
+impl std::error::Error for BadExternalInput {}
+
+/// A sequence of values for the external input register, each
+/// taking effect at a given (simulated) time.
+///
+/// Each value is given as `TIME_MS VALUE`, where TIME_MS is the
+/// simulated time in milliseconds and VALUE is an octal number of up
+/// to 36 bits.  The values must be in time order.
+#[derive(Debug, Default)]
+pub struct ExternalInputSchedule {
+    values: VecDeque<(Duration, Unsigned36Bit)>,
+}
+
+impl ExternalInputSchedule {
+    /// Parse a file containing one value per line.  Blank lines and
+    /// lines starting with `#` are ignored.
+    pub fn parse_file(text: &str) -> Result<ExternalInputSchedule, BadExternalInput> {
+        let mut schedule = ExternalInputSchedule::default();
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            schedule.push(&format!("line {}", index + 1), &fields)?;
+        }
+        Ok(schedule)
+    }
+
+    /// Parse a comma-separated list of values, each of the form
+    /// `TIME_MS:VALUE`.
+    pub fn parse_list(list: &str) -> Result<ExternalInputSchedule, BadExternalInput> {
+        let mut schedule = ExternalInputSchedule::default();
+        for (index, item) in list.split(',').enumerate() {
+            let fields: Vec<&str> = item.split(':').map(str::trim).collect();
+            schedule.push(&format!("item {}", index + 1), &fields)?;
+        }
+        Ok(schedule)
+    }
+
+    fn push(&mut self, location: &str, fields: &[&str]) -> Result<(), BadExternalInput> {
+        let fail = |message: String| BadExternalInput {
+            location: location.to_string(),
+            message,
+        };
+        let (time, value) = match fields {
+            [time, value] => (time, value),
+            _ => {
+                return Err(fail(format!(
+                    "expected a time and a value but got {} fields",
+                    fields.len()
+                )));
+            }
+        };
+        let millis: u64 = time
+            .parse()
+            .map_err(|e| fail(format!("bad time '{time}': {e}")))?;
+        let value: Unsigned36Bit = u64::from_str_radix(value, 8)
+            .map_err(|e| fail(format!("bad octal value '{value}': {e}")))
+            .and_then(|n| {
+                Unsigned36Bit::try_from(n)
+                    .map_err(|_| fail(format!("value {value} does not fit in 36 bits")))
+            })?;
+        let when = Duration::from_millis(millis);
+        if let Some((prev, _)) = self.values.back() {
+            if *prev > when {
+                return Err(fail("values are not in time order".to_string()));
+            }
+        }
+        self.values.push_back((when, value));
+        Ok(())
+    }
+
+    /// The time at which the next value is due, if there is one.
+    pub fn next_time(&self) -> Option<Duration> {
+        self.values.front().map(|(when, _)| *when)
+    }
+
+    /// Remove and return the next value if it is due at time `now`.
+    pub fn next_due(&mut self, now: Duration) -> Option<Unsigned36Bit> {
+        match self.values.front() {
+            Some((when, _)) if *when <= now => self.values.pop_front().map(|(_, value)| value),
+            _ => None,
+        }
+    }
+}
+
+#[test]
+fn test_parse_external_input() {
+    let mut schedule = ExternalInputSchedule::parse_file("# start\n0 17\n\n250 777777777777\n")
+        .expect("file should be valid");
+    assert_eq!(schedule.next_time(), Some(Duration::ZERO));
+    assert_eq!(schedule.next_due(Duration::ZERO), Some(u36!(0o17)));
+    assert_eq!(schedule.next_due(Duration::from_millis(249)), None);
+    assert_eq!(schedule.next_time(), Some(Duration::from_millis(250)));
+    assert_eq!(
+        schedule.next_due(Duration::from_millis(250)),
+        Some(u36!(0o777_777_777_777))
+    );
+    assert_eq!(schedule.next_time(), None);
+
+    let mut schedule =
+        ExternalInputSchedule::parse_list("0:1, 10:2,10:3").expect("list should be valid");
+    let values: Vec<Unsigned36Bit> =
+        std::iter::from_fn(|| schedule.next_due(Duration::from_millis(10))).collect();
+    assert_eq!(values, vec![u36!(1), u36!(2), u36!(3)]);
+}
+
+#[test]
+fn test_parse_external_input_errors() {
+    let fails =
+        |result: Result<ExternalInputSchedule, BadExternalInput>, expected: &str| match result {
+            Ok(schedule) => panic!("expected an error but got {schedule:?}"),
+            Err(e) => assert!(
+                e.to_string().contains(expected),
+                "error '{e}' should mention '{expected}'"
+            ),
+        };
+    // Out of time order.
+    fails(
+        ExternalInputSchedule::parse_file("10 1\n5 2\n"),
+        "line 2: values are not in time order",
+    );
+    fails(
+        ExternalInputSchedule::parse_list("10:1,5:2"),
+        "item 2: values are not in time order",
+    );
+    // Values must be octal and fit in 36 bits.
+    fails(
+        ExternalInputSchedule::parse_list("0:8"),
+        "bad octal value '8'",
+    );
+    fails(
+        ExternalInputSchedule::parse_list("0:1000000000000"),
+        "does not fit in 36 bits",
+    );
+    // Malformed items.
+    fails(ExternalInputSchedule::parse_file("10\n"), "got 1 fields");
+    fails(ExternalInputSchedule::parse_list("0:1:2"), "got 3 fields");
+    fails(ExternalInputSchedule::parse_list("0:1,"), "item 2");
+    fails(
+        ExternalInputSchedule::parse_list("soon:1"),
+        "bad time 'soon'",
+    );
+}
@@ -0,0 +1,115 @@
+use std::collections::VecDeque;
+use std::fmt::{self, Display, Formatter};
+use std::time::Duration;
+
+/// A single scripted movement of the light pen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PenMove {
+    pub when: Duration,
+    pub x: u16,
+    pub y: u16,
+    pub tracking: bool,
+}
+
+#[derive(Debug)]
+pub struct BadPenScript {
+    line_number: usize,
+    message: String,
+}
+
+impl Display for BadPenScript {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(
+            f,
+            "light pen script line {}: {}",
+            self.line_number, self.message
+        )
+    }
+}
+
+impl std::error::Error for BadPenScript {}
+
+/// A sequence of light pen movements, each taking effect at a given
+/// (simulated) time.
+///
+/// Each line of a script looks like `TIME_MS X Y [on|off]`, where
+/// TIME_MS is the simulated time in milliseconds and X and Y are
+/// display coordinates.  The final field says whether the pen is
+/// tracking (it defaults to "on").  Blank lines and lines starting
+/// with `#` are ignored.  The lines must be in time order.
+#[derive(Debug, Default)]
+pub struct LightPenScript {
+    moves: VecDeque<PenMove>,
+}
+
+impl LightPenScript {
+    pub fn parse(text: &str) -> Result<LightPenScript, BadPenScript> {
+        let mut moves: VecDeque<PenMove> = VecDeque::new();
+        for (index, line) in text.lines().enumerate() {
+            let line_number = index + 1;
+            let fail = |message: String| BadPenScript {
+                line_number,
+                message,
+            };
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            let (time, x, y, tracking) = match fields.as_slice() {
+                [time, x, y] => (time, x, y, "on"),
+                [time, x, y, tracking] => (time, x, y, *tracking),
+                _ => {
+                    return Err(fail(format!(
+                        "expected 3 or 4 fields but got {}",
+                        fields.len()
+                    )));
+                }
+            };
+            let millis: u64 = time
+                .parse()
+                .map_err(|e| fail(format!("bad time '{time}': {e}")))?;
+            let coord = |s: &str| -> Result<u16, BadPenScript> {
+                match s.parse::<u16>() {
+                    Ok(n) if n < cpu::DISPLAY_SIZE => Ok(n),
+                    Ok(n) => Err(fail(format!("coordinate {n} is off the screen"))),
+                    Err(e) => Err(fail(format!("bad coordinate '{s}': {e}"))),
+                }
+            };
+            let tracking = match tracking {
+                "on" => true,
+                "off" => false,
+                other => {
+                    return Err(fail(format!("expected 'on' or 'off' but got '{other}'")));
+                }
+            };
+            let pen_move = PenMove {
+                when: Duration::from_millis(millis),
+                x: coord(x)?,
+                y: coord(y)?,
+                tracking,
+            };
+            if let Some(prev) = moves.back() {
+                if prev.when > pen_move.when {
+                    return Err(fail("lines are not in time order".to_string()));
+                }
+            }
+            moves.push_back(pen_move);
+        }
+        Ok(LightPenScript { moves })
+    }
+
+    /// The time at which the next movement is due, if there is one.
+    pub fn next_time(&self) -> Option<Duration> {
+        self.moves.front().map(|pen_move| pen_move.when)
+    }
+
+    /// Remove and return the next movement if it is due at time
+    /// `now`.
+    pub fn next_due(&mut self, now: Duration) -> Option<PenMove> {
+        match self.moves.front() {
+            Some(pen_move) if pen_move.when <= now => self.moves.pop_front(),
+            _ => None,
+        }
+    }
+}
@@ -1,13 +1,14 @@
-use std::io::{stdout, Write};
+use std::fs::File;
+use std::io::{BufWriter, Write};
 
-use termcolor::{self, ColorChoice, ColorSpec, StandardStream, WriteColor};
+use termcolor::{self, ColorChoice, ColorSpec, NoColor, StandardStream, WriteColor};
 use tracing::{event, Level};
 
 use base::charset::{self, DescribedChar, LincolnChar, LincolnState};
 
 pub struct LincolnStreamWriter {
     current_attributes: LincolnState,
-    stream: StandardStream,
+    stream: Box<dyn WriteColor>,
 }
 
 fn get_colour_choice() -> termcolor::ColorChoice {
@@ -19,10 +20,20 @@ fn get_colour_choice() -> termcolor::ColorChoice {
 }
 
 impl LincolnStreamWriter {
+    /// Create a writer which sends its output to standard output.
     pub fn new() -> LincolnStreamWriter {
         LincolnStreamWriter {
             current_attributes: LincolnState::default(),
-            stream: StandardStream::stdout(get_colour_choice()),
+            stream: Box::new(StandardStream::stdout(get_colour_choice())),
+        }
+    }
+
+    /// Create a writer which sends its output to a file.  Colour
+    /// changes are not recorded in the file.
+    pub fn to_file(file: File) -> LincolnStreamWriter {
+        LincolnStreamWriter {
+            current_attributes: LincolnState::default(),
+            stream: Box::new(NoColor::new(BufWriter::new(file))),
         }
     }
 
@@ -80,9 +91,7 @@ impl LincolnStreamWriter {
                 base
             }
         };
-        let stdout = stdout();
-        let mut handle = stdout.lock();
-        write!(handle, "{}", to_emit).and_then(|()| handle.flush())
+        write!(self.stream, "{}", to_emit).and_then(|()| self.stream.flush())
     }
 
     pub fn disconnect(&mut self) {
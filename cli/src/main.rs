@@ -1,12 +1,19 @@
 /// Simulate the historic TX-2 computer
 mod clock;
+mod datrac;
+mod display;
+mod external;
+mod lightpen;
 mod lw;
 mod sleep;
+mod xerox;
 
+use std::cmp::{max, min};
 use std::ffi::{OsStr, OsString};
 use std::fmt::{self, Debug, Display, Formatter};
-use std::fs::OpenOptions;
-use std::io::{BufReader, Read};
+use std::fs::{File, OpenOptions};
+use std::hash::{BuildHasher, Hasher};
+use std::io::{BufReader, BufWriter, Read, Write};
 use std::str::FromStr;
 use std::time::Duration;
 
@@ -18,19 +25,115 @@ use tracing_subscriber::prelude::*;
 use base::prelude::*;
 use clock::{BasicClock, Clock};
 use cpu::{
-    self, Alarm, AlarmDetails, MemoryConfiguration, OutputEvent, ResetMode, RunMode, Tx2,
-    UnmaskedAlarm,
+    self, Context, MemoryConfiguration, OutputEvent, PeripheralConfiguration, ResetMode, RunMode,
+    Tx2, UnmaskedAlarm,
 };
+use display::{FrameDumper, FrameFormat};
+use external::ExternalInputSchedule;
+use lightpen::LightPenScript;
+use lw::LincolnStreamWriter;
+use xerox::PagePrinter;
 
 // Thanks to Google for allowing this code to be open-sourced.  I
 // generally prefer to correspond about this project using my
 // personal email address rather than my work one, though.
 const AUTHOR: &str = "James Youngman <james@youngman.org>";
 
+/// The host-side counterparts of the simulated peripherals (each of
+/// which is optional).
+struct HostDevices {
+    /// Collects points plotted on the display (unit 60).
+    frames: Option<FrameDumper>,
+    /// Moves the light pen (unit 55).
+    light_pen: Option<LightPenScript>,
+    /// Receives the tape punched by unit 63.
+    punch: Option<BufWriter<File>>,
+    /// Prints the output of the first Lincoln Writer (unit 66).
+    lw66: Option<LincolnStreamWriter>,
+    /// Prints the output of the second Lincoln Writer (unit 72).
+    lw72: Option<LincolnStreamWriter>,
+    /// Writes the pages printed by the Xerox printer (unit 51).
+    printer: Option<PagePrinter>,
+    /// Sets the external input register.
+    external_input: Option<ExternalInputSchedule>,
+}
+
+impl HostDevices {
+    fn lincoln_writer(&mut self, unit: Unsigned6Bit) -> Option<&mut LincolnStreamWriter> {
+        match u8::from(unit) {
+            0o66 => self.lw66.as_mut(),
+            0o72 => self.lw72.as_mut(),
+            _ => None,
+        }
+    }
+
+    /// The (simulated) time at which the next scripted input is due.
+    fn next_scripted_input(&self) -> Option<Duration> {
+        let pen = self.light_pen.as_ref().and_then(LightPenScript::next_time);
+        let external = self
+            .external_input
+            .as_ref()
+            .and_then(ExternalInputSchedule::next_time);
+        pen.into_iter().chain(external).min()
+    }
+
+    /// Deliver the scripted inputs which are due at the time of
+    /// `ctx`.
+    fn apply_scripted_inputs(&mut self, tx2: &mut Tx2, ctx: &Context) {
+        if let Some(script) = self.light_pen.as_mut() {
+            while let Some(pen_move) = script.next_due(ctx.simulated_time) {
+                if let Err(e) =
+                    tx2.light_pen_position(ctx, pen_move.x, pen_move.y, pen_move.tracking)
+                {
+                    event!(Level::ERROR, "failed to move light pen: {}", e);
+                }
+            }
+        }
+        if let Some(schedule) = self.external_input.as_mut() {
+            while let Some(word) = schedule.next_due(ctx.simulated_time) {
+                tx2.set_external_input_register(word);
+            }
+        }
+    }
+}
+
+/// Advance the simulated clock to the time of the next TX-2 tick, or
+/// of the next scripted input if that is sooner (otherwise, when all
+/// sequences are waiting, a scripted input could be delivered long
+/// after it was due).  Deliver the scripted inputs which are due.
+/// Returns the context for the tick, or None if the TX-2 does not
+/// need to tick yet.
+fn advance_clock(
+    tx2: &mut Tx2,
+    clk: &mut BasicClock,
+    sleeper: &mut sleep::MinimalSleeper,
+    sleep_multiplier: Option<f64>,
+    host: &mut HostDevices,
+) -> Option<Context> {
+    let now = clk.now();
+    let next = match host.next_scripted_input() {
+        Some(scripted) => min(tx2.next_tick(), max(scripted, now)),
+        None => tx2.next_tick(),
+    };
+    if now < next {
+        let interval = next - now;
+        sleep::time_passes(clk, sleeper, &interval, sleep_multiplier);
+    }
+    clk.advance_to_simulated_time(next);
+    let ctx = clk.make_fresh_context();
+    host.apply_scripted_inputs(tx2, &ctx);
+    if tx2.next_tick() <= ctx.simulated_time {
+        Some(ctx)
+    } else {
+        None
+    }
+}
+
 fn run(
     tx2: &mut Tx2,
     clk: &mut BasicClock,
     sleep_multiplier: Option<f64>,
+    host: &mut HostDevices,
 ) -> Result<(), Box<dyn std::error::Error>> {
     if let Err(e) = tx2.codabo(&clk.make_fresh_context(), &ResetMode::ResetTSP) {
         event!(Level::ERROR, "CODABO failed: {}", e);
@@ -47,12 +150,18 @@ fn run(
     }
     tx2.set_run_mode(RunMode::Running);
 
-    match run_until_alarm(tx2, clk, sleep_multiplier) {
-        UnmaskedAlarm {
+    match run_until_alarm(tx2, clk, sleep_multiplier, host) {
+        Err(e) => {
+            // The TX-2 program did nothing wrong; the host failed to
+            // accept the output of one of the simulated peripherals.
+            event!(Level::ERROR, "Execution stopped by an output error: {}", e);
+            return Err(Box::new(e));
+        }
+        Ok(UnmaskedAlarm {
             alarm,
             address: Some(addr),
             when: _,
-        } => {
+        }) => {
             event!(
                 Level::ERROR,
                 "Execution stopped at address  {:o}: {}",
@@ -60,14 +169,34 @@ fn run(
                 alarm
             );
         }
-        UnmaskedAlarm {
+        Ok(UnmaskedAlarm {
             alarm,
             address: None,
             when: _,
-        } => {
+        }) => {
             event!(Level::ERROR, "Execution stopped: {}", alarm);
         }
     };
+    if let Some(frames) = host.frames.as_mut() {
+        // Write the final state of the display.
+        if let Err(e) = frames.write_frame(clk.now()) {
+            event!(Level::ERROR, "Failed to write display frame: {}", e);
+            return Err(Box::new(e));
+        }
+    }
+    if let Some(printer) = host.printer.as_mut() {
+        // Write the final (partly printed) page.
+        if let Err(e) = printer.finish() {
+            event!(Level::ERROR, "Failed to write printed page: {}", e);
+            return Err(Box::new(e));
+        }
+    }
+    if let Some(punch) = host.punch.as_mut() {
+        if let Err(e) = punch.flush() {
+            event!(Level::ERROR, "Failed to write punched tape: {}", e);
+            return Err(Box::new(e));
+        }
+    }
     if let Err(e) = tx2.disconnect_all_devices(&clk.make_fresh_context()) {
         event!(Level::ERROR, "Failed in device shutdown: {}", e);
         return Err(Box::new(e));
@@ -75,59 +204,107 @@ fn run(
     Ok(())
 }
 
+/// Run the simulation until an unmasked alarm occurs.  Failure to
+/// write the output of a peripheral on the host is reported as an
+/// error, since it is not the fault of the simulated program.
 fn run_until_alarm(
     tx2: &mut Tx2,
     clk: &mut BasicClock,
     sleep_multiplier: Option<f64>,
-) -> UnmaskedAlarm {
+    host: &mut HostDevices,
+) -> Result<UnmaskedAlarm, std::io::Error> {
     let mut sleeper = sleep::MinimalSleeper::new(Duration::from_millis(2));
-    let mut lw66 = lw::LincolnStreamWriter::new();
-
-    let result: UnmaskedAlarm = loop {
-        {
-            let now = clk.now();
-            let next = tx2.next_tick();
-            if now < next {
-                let interval = next - now;
-                sleep::time_passes(clk, &mut sleeper, &interval, sleep_multiplier);
+
+    let result: Result<UnmaskedAlarm, std::io::Error> = loop {
+        let tick_context = match advance_clock(tx2, clk, &mut sleeper, sleep_multiplier, host) {
+            Some(ctx) => ctx,
+            None => {
+                continue;
             }
-            clk.advance_to_simulated_time(next);
-        }
-        let tick_context = clk.make_fresh_context();
+        };
         match tx2.tick(&tick_context) {
             Ok(maybe_output) => {
                 match maybe_output {
                     None => (),
                     Some(OutputEvent::LincolnWriterPrint { unit, ch }) => {
-                        if unit == u6!(0o66) {
-                            if let Err(e) = lw66.write(ch) {
-                                event!(Level::ERROR, "output error: {}", e);
-                                // TODO: change state of the output unit to
-                                // indicate that there has been a failure,
-                                // instead of just terminating the simulation.
-                                break UnmaskedAlarm {
-                                    alarm: Alarm {
-                                        sequence: None,
-                                        details: AlarmDetails::MISAL {
-                                            affected_unit: u6!(0o66),
-                                        },
-                                    },
-                                    address: None,
-                                    when: tick_context.simulated_time,
-                                };
+                        match host.lincoln_writer(unit) {
+                            Some(lw) => {
+                                if let Err(e) = lw.write(ch) {
+                                    event!(
+                                        Level::ERROR,
+                                        "failed to print output of Lincoln Writer unit {:o}: {}",
+                                        unit,
+                                        e
+                                    );
+                                    break Err(e);
+                                }
+                            }
+                            None => {
+                                event!(
+                                    Level::WARN,
+                                    "discarding output for Lincoln Writer unit {:o} (use --lw{:o}-output to keep it)",
+                                    unit,
+                                    unit,
+                                );
+                            }
+                        }
+                    }
+                    Some(OutputEvent::DisplayPoint { x, y, intensity }) => {
+                        if let Some(frames) = host.frames.as_mut() {
+                            frames.plot(x, y, intensity, tick_context.simulated_time);
+                        }
+                    }
+                    Some(OutputEvent::PunchedLine { line }) => match host.punch.as_mut() {
+                        Some(punch) => {
+                            if let Err(e) = punch.write_all(&[line]) {
+                                event!(Level::ERROR, "failed to write punched tape: {}", e);
+                                break Err(e);
+                            }
+                        }
+                        None => {
+                            event!(
+                                Level::WARN,
+                                "discarding punched tape line {:03o} (use --punch-output to keep it)",
+                                line,
+                            );
+                        }
+                    },
+                    Some(OutputEvent::MiscOutputWord { word }) => {
+                        event!(
+                            Level::INFO,
+                            "miscellaneous output word (unit 75) is now {:012o}",
+                            word
+                        );
+                    }
+                    Some(OutputEvent::XeroxPrint {
+                        page,
+                        line,
+                        column,
+                        ch,
+                    }) => match host.printer.as_mut() {
+                        Some(printer) => {
+                            if let Err(e) = printer.print(page, line, column, ch) {
+                                event!(Level::ERROR, "failed to write printed page: {}", e);
+                                break Err(e);
                             }
-                        } else {
+                        }
+                        None => {
                             event!(
                                 Level::WARN,
-                                "discarding Lincoln Writer output for unit {:o}",
-                                unit,
+                                "discarding Xerox printer output (use --xerox-output-dir to keep it)",
                             );
                         }
+                    },
+                }
+                if let Some(frames) = host.frames.as_mut() {
+                    if let Err(e) = frames.maybe_write_frame(tick_context.simulated_time) {
+                        event!(Level::ERROR, "failed to write display frame: {}", e);
+                        break Err(e);
                     }
                 }
             }
             Err(unmasked_alarm) => {
-                break unmasked_alarm;
+                break Ok(unmasked_alarm);
             }
         }
         let next_tick = tx2.next_tick();
@@ -140,7 +317,12 @@ fn run_until_alarm(
             );
         }
     };
-    lw66.disconnect();
+    for lw in [host.lw66.as_mut(), host.lw72.as_mut()]
+        .into_iter()
+        .flatten()
+    {
+        lw.disconnect();
+    }
     result
 }
 
@@ -171,6 +353,32 @@ impl FromStr for PanicOnUnmaskedAlarm {
     }
 }
 
+/// How to seed the random number generator (unit 61).
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum RngSeed {
+    /// Use this seed.
+    Fixed(u64),
+    /// Choose a seed using the host's source of randomness.
+    Host,
+}
+
+impl FromStr for RngSeed {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s {
+            "host" => Ok(RngSeed::Host),
+            _ => match s.parse::<u64>() {
+                Ok(seed) => Ok(RngSeed::Fixed(seed)),
+                Err(e) => Err(format!(
+                    "unexpected value '{}': expected 'host' or a number: {}",
+                    s, e
+                )),
+            },
+        }
+    }
+}
+
 /// Command-line simulator for the historical TX-2 computer
 #[derive(Parser, Debug)]
 #[command(author = AUTHOR, version, about, long_about = None)]
@@ -185,6 +393,98 @@ struct Cli {
     #[arg(action = Set, long = "panic-on-unmasked-alarm", value_enum)]
     panic_on_unmasked_alarm: Option<PanicOnUnmaskedAlarm>,
 
+    /// Execute opcode 2 as XEQ (execute the instruction at the
+    /// operand address).  This is experimental, since we don't know
+    /// whether the TX-2 actually had an XEQ instruction; without this
+    /// option, opcode 2 raises OCSAL.
+    #[arg(long = "enable-xeq")]
+    enable_xeq: bool,
+
+    /// Periodically write the contents of the display (unit 60) as
+    /// image files in this directory.
+    #[arg(action = Set, long = "display-frames-dir")]
+    display_frames_dir: Option<OsString>,
+
+    /// File format for display frames.
+    #[arg(action = Set, long = "display-frame-format", value_enum, default_value = "ppm")]
+    display_frame_format: FrameFormat,
+
+    /// Interval between display frames, in milliseconds of simulated
+    /// time.
+    #[arg(action = Set, long = "display-frame-interval-ms", default_value_t = 40)]
+    display_frame_interval_ms: u64,
+
+    /// Seed for the random number generator (unit 61); either a
+    /// number, or 'host' to choose a seed using the host's source of
+    /// randomness.  By default a fixed seed is used, so that runs are
+    /// reproducible.
+    #[arg(action = Set, long = "rng-seed")]
+    rng_seed: Option<RngSeed>,
+
+    /// File containing a script of light pen movements.  Each line
+    /// has the form "TIME_MS X Y [on|off]".
+    #[arg(action = Set, long = "light-pen-script")]
+    light_pen_script: Option<OsString>,
+
+    /// Write tape punched by the paper tape punch (unit 63) to this
+    /// file.  The file can be read by the paper tape reader.
+    #[arg(action = Set, long = "punch-output")]
+    punch_output: Option<OsString>,
+
+    /// Print the output of the first Lincoln Writer (unit 66) to this
+    /// file instead of to standard output.
+    #[arg(action = Set, long = "lw66-output")]
+    lw66_output: Option<OsString>,
+
+    /// Print the output of the second Lincoln Writer (unit 72) to this
+    /// file.  If this option is not given, output printed on the
+    /// second Lincoln Writer is discarded.
+    #[arg(action = Set, long = "lw72-output")]
+    lw72_output: Option<OsString>,
+
+    /// Write each page printed by the Xerox printer (unit 51) as a
+    /// text file in this directory.
+    #[arg(action = Set, long = "xerox-output-dir")]
+    xerox_output_dir: Option<OsString>,
+
+    /// Also write each printed page as an SVG file.
+    #[arg(long = "xerox-svg")]
+    xerox_svg: bool,
+
+    /// Number of lines on each page printed by the Xerox printer.
+    #[arg(action = Set, long = "xerox-lines-per-page", default_value_t = PeripheralConfiguration::default().xerox_lines_per_page)]
+    xerox_lines_per_page: usize,
+
+    /// Number of characters on each line printed by the Xerox
+    /// printer.  Longer lines are wrapped.
+    #[arg(action = Set, long = "xerox-line-width", default_value_t = PeripheralConfiguration::default().xerox_line_width)]
+    xerox_line_width: usize,
+
+    /// Feed the DATRAC A/D converter (unit 50) with samples from this
+    /// file.  Files whose names end in .wav are read as (8-bit or
+    /// 16-bit PCM) WAV files; other files are read as CSV, one sample
+    /// per line.
+    #[arg(action = Set, long = "datrac-input")]
+    datrac_input: Option<OsString>,
+
+    /// The DATRAC sample rate in samples per second (of simulated
+    /// time).  By default, the rate recorded in the WAV file is used.
+    #[arg(action = Set, long = "datrac-sample-rate")]
+    datrac_sample_rate: Option<u32>,
+
+    /// File of values for the external input register (V-memory
+    /// location 377621).  Each line has the form "TIME_MS VALUE",
+    /// where VALUE is in octal.
+    #[arg(action = Set, long = "external-input", conflicts_with = "external_input_values")]
+    external_input: Option<OsString>,
+
+    /// Values for the external input register, as a comma-separated
+    /// list of "TIME_MS:VALUE" items (VALUE is in octal).  For
+    /// example, "0:0,1000:777" sets the register to 777 after one
+    /// second of simulated time.
+    #[arg(action = Set, long = "external-input-values")]
+    external_input_values: Option<String>,
+
     /// File containing paper tape data
     #[arg(action = Set)]
     tape: Option<OsString>,
@@ -205,6 +505,17 @@ impl Display for BadSpeedMultiplier {
 
 impl std::error::Error for BadSpeedMultiplier {}
 
+#[derive(Debug)]
+struct BadDatracSampleRate(&'static str);
+
+impl Display for BadDatracSampleRate {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        f.write_str(self.0)
+    }
+}
+
+impl std::error::Error for BadDatracSampleRate {}
+
 fn run_simulator() -> Result<(), Box<dyn std::error::Error>> {
     let cli = Cli::parse();
 
@@ -315,13 +626,129 @@ fn run_simulator() -> Result<(), Box<dyn std::error::Error>> {
         Some(PanicOnUnmaskedAlarm::No) | None => cpu::PanicOnUnmaskedAlarm::No,
     };
     let initial_context = clk.make_fresh_context();
-    let mut tx2 = Tx2::new(&initial_context, panic_on_unmasked_alarm, &mem_config);
+    let mut peripheral_config = PeripheralConfiguration::default();
+    match cli.rng_seed {
+        None => (),
+        Some(RngSeed::Fixed(seed)) => {
+            peripheral_config.rng_seed = seed;
+        }
+        Some(RngSeed::Host) => {
+            peripheral_config.rng_seed = std::collections::hash_map::RandomState::new()
+                .build_hasher()
+                .finish();
+            // Log the seed so that the run can be repeated.
+            event!(
+                Level::INFO,
+                "--rng-seed=host: the random number generator seed is {}",
+                peripheral_config.rng_seed
+            );
+        }
+    }
+    peripheral_config.xerox_lines_per_page = cli.xerox_lines_per_page;
+    peripheral_config.xerox_line_width = cli.xerox_line_width;
+    let mut tx2 = Tx2::new(
+        &initial_context,
+        panic_on_unmasked_alarm,
+        &mem_config,
+        &peripheral_config,
+    );
+    if cli.enable_xeq {
+        event!(
+            Level::INFO,
+            "--enable-xeq: opcode 2 will be executed as XEQ"
+        );
+        tx2.set_xeq_enabled(true);
+    }
     if let Some(tape) = tape_data {
         if let Err(e) = tx2.mount_tape(&initial_context, tape) {
             return Err(Box::new(e));
         }
     }
-    run(&mut tx2, &mut clk, sleep_multiplier)
+    if let Some(file_name) = cli.datrac_input.as_ref() {
+        let sample_file = datrac::read_sample_file(file_name)?;
+        let sample_rate: u32 = match (cli.datrac_sample_rate, sample_file.sample_rate) {
+            (Some(rate), _) | (None, Some(rate)) => rate,
+            (None, None) => {
+                return Err(Box::new(BadDatracSampleRate(
+                    "--datrac-sample-rate must be specified for CSV sample files",
+                )));
+            }
+        };
+        if sample_rate == 0 {
+            return Err(Box::new(BadDatracSampleRate(
+                "the DATRAC sample rate must not be zero",
+            )));
+        }
+        event!(
+            Level::INFO,
+            "--datrac-input: {} samples at {} samples per second",
+            sample_file.samples.len(),
+            sample_rate
+        );
+        if let Err(e) = tx2.datrac_samples(&initial_context, sample_file.samples, sample_rate) {
+            return Err(Box::new(e));
+        }
+    }
+    let frames: Option<FrameDumper> = cli.display_frames_dir.map(|dir| {
+        event!(
+            Level::INFO,
+            "--display-frames-dir: writing display frames to {}",
+            dir.to_string_lossy()
+        );
+        FrameDumper::new(
+            dir,
+            cli.display_frame_format,
+            Duration::from_millis(cli.display_frame_interval_ms),
+        )
+    });
+    let light_pen: Option<LightPenScript> = match cli.light_pen_script.as_ref() {
+        None => None,
+        Some(file_name) => {
+            let text = std::fs::read_to_string(file_name)?;
+            Some(LightPenScript::parse(&text)?)
+        }
+    };
+    let external_input: Option<ExternalInputSchedule> = match (
+        cli.external_input.as_ref(),
+        cli.external_input_values.as_ref(),
+    ) {
+        (Some(file_name), _) => {
+            let text = std::fs::read_to_string(file_name)?;
+            Some(ExternalInputSchedule::parse_file(&text)?)
+        }
+        (None, Some(list)) => Some(ExternalInputSchedule::parse_list(list)?),
+        (None, None) => None,
+    };
+    let punch: Option<BufWriter<File>> = match cli.punch_output.as_ref() {
+        None => None,
+        Some(file_name) => Some(BufWriter::new(File::create(file_name)?)),
+    };
+    let lw66: LincolnStreamWriter = match cli.lw66_output.as_ref() {
+        None => LincolnStreamWriter::new(),
+        Some(file_name) => LincolnStreamWriter::to_file(File::create(file_name)?),
+    };
+    let lw72: Option<LincolnStreamWriter> = match cli.lw72_output.as_ref() {
+        None => None,
+        Some(file_name) => Some(LincolnStreamWriter::to_file(File::create(file_name)?)),
+    };
+    let printer: Option<PagePrinter> = cli.xerox_output_dir.map(|dir| {
+        event!(
+            Level::INFO,
+            "--xerox-output-dir: writing printed pages to {}",
+            dir.to_string_lossy()
+        );
+        PagePrinter::new(dir, cli.xerox_svg, peripheral_config.xerox_page_size())
+    });
+    let mut host = HostDevices {
+        frames,
+        light_pen,
+        punch,
+        lw66: Some(lw66),
+        lw72,
+        printer,
+        external_input,
+    };
+    run(&mut tx2, &mut clk, sleep_multiplier, &mut host)
 }
 
 fn main() {
@@ -335,3 +762,82 @@ fn main() {
         }
     }
 }
+
+#[test]
+fn test_scripted_light_pen_move_is_delivered_on_time() {
+    let mut clk = BasicClock::new();
+    let mut tx2 = Tx2::new(
+        &clk.make_fresh_context(),
+        cpu::PanicOnUnmaskedAlarm::No,
+        &MemoryConfiguration {
+            with_u_memory: false,
+        },
+        &PeripheralConfiguration::default(),
+    );
+    // With no program running, the TX-2 has nothing to do until
+    // well after the pen is due to move.
+    if let Err(e) = tx2.tick(&clk.make_fresh_context()) {
+        panic!("first tick failed: {e}");
+    }
+    assert!(tx2.next_tick() > Duration::from_millis(100));
+
+    let mut host = HostDevices {
+        frames: None,
+        light_pen: Some(
+            LightPenScript::parse("100 10 20 on\n").expect("test script should be valid"),
+        ),
+        punch: None,
+        lw66: None,
+        lw72: None,
+        printer: None,
+        external_input: None,
+    };
+    let mut sleeper = sleep::MinimalSleeper::new(Duration::from_millis(2));
+    let tick = advance_clock(&mut tx2, &mut clk, &mut sleeper, None, &mut host);
+    assert_eq!(clk.now(), Duration::from_millis(100));
+    assert_eq!(host.next_scripted_input(), None);
+    // Moving the pen makes the TX-2 poll it.
+    assert!(tick.is_some());
+    let statuses = tx2
+        .sequence_statuses(&clk.make_fresh_context())
+        .expect("unit statuses should be available");
+    match statuses.get(&cpu::LIGHT_PEN) {
+        Some(pen) => assert!(pen.text_info.starts_with("Pen at (10, 20)")),
+        None => panic!("the light pen is not attached"),
+    }
+}
+
+#[test]
+fn test_scheduled_external_input_is_delivered_on_time() {
+    let mut clk = BasicClock::new();
+    let mut tx2 = Tx2::new(
+        &clk.make_fresh_context(),
+        cpu::PanicOnUnmaskedAlarm::No,
+        &MemoryConfiguration {
+            with_u_memory: false,
+        },
+        &PeripheralConfiguration::default(),
+    );
+    if let Err(e) = tx2.tick(&clk.make_fresh_context()) {
+        panic!("first tick failed: {e}");
+    }
+    assert!(tx2.next_tick() > Duration::from_millis(100));
+
+    let mut host = HostDevices {
+        frames: None,
+        light_pen: None,
+        punch: None,
+        lw66: None,
+        lw72: None,
+        printer: None,
+        external_input: Some(
+            ExternalInputSchedule::parse_list("100:17").expect("test schedule should be valid"),
+        ),
+    };
+    let mut sleeper = sleep::MinimalSleeper::new(Duration::from_millis(2));
+    let tick = advance_clock(&mut tx2, &mut clk, &mut sleeper, None, &mut host);
+    assert_eq!(clk.now(), Duration::from_millis(100));
+    assert_eq!(host.next_scripted_input(), None);
+    // Setting the register doesn't wake the TX-2 up early.
+    assert!(tick.is_none());
+}
@@ -0,0 +1,210 @@
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::PathBuf;
+
+use tracing::{event, Level};
+
+use base::charset::{Colour, DescribedChar, LincolnChar, Script};
+
+/// Size of the characters in SVG output, in pixels.
+const SVG_FONT_SIZE: usize = 12;
+const SVG_CHAR_WIDTH: f64 = 7.2;
+const SVG_LINE_HEIGHT: usize = 16;
+const SVG_MARGIN: usize = 24;
+
+/// The characters printed on a single page.  More than one character
+/// may be printed in the same place (for example, by backspacing and
+/// overstriking).
+struct Page {
+    number: usize,
+    cells: BTreeMap<(usize, usize), Vec<DescribedChar>>,
+}
+
+fn char_to_show(ch: &DescribedChar) -> char {
+    match (ch.unicode_representation, ch.base_char) {
+        (Some(uch), _) => uch,
+        (None, LincolnChar::UnicodeBaseChar(base)) => base,
+        (None, LincolnChar::Unprintable(_)) => ' ',
+    }
+}
+
+/// Collects the characters printed by the Xerox printer (unit 51)
+/// and writes each page to a file once it is complete.  Pages on
+/// which nothing was printed are written as blank pages, except
+/// those which follow the last printed character.
+pub struct PagePrinter {
+    dir: PathBuf,
+    svg: bool,
+    lines_per_page: usize,
+    line_width: usize,
+    current: Option<Page>,
+    /// The number of the first page not yet written.
+    next_page: usize,
+}
+
+impl PagePrinter {
+    /// `page_size` is (lines per page, line width), as given by
+    /// [`cpu::PeripheralConfiguration::xerox_page_size`].
+    pub fn new(dir: OsString, svg: bool, page_size: (usize, usize)) -> PagePrinter {
+        let (lines_per_page, line_width) = page_size;
+        PagePrinter {
+            dir: PathBuf::from(dir),
+            svg,
+            lines_per_page,
+            line_width,
+            current: None,
+            next_page: 0,
+        }
+    }
+
+    pub fn print(
+        &mut self,
+        page: usize,
+        line: usize,
+        column: usize,
+        ch: DescribedChar,
+    ) -> Result<(), std::io::Error> {
+        match self.current.as_ref() {
+            Some(current) if current.number == page => (),
+            _ => {
+                // The previous page is finished, as are any pages
+                // which were fed out without anything being printed
+                // on them.
+                self.finish()?;
+                for number in self.next_page..page {
+                    self.write(&Page {
+                        number,
+                        cells: BTreeMap::new(),
+                    })?;
+                }
+                self.current = Some(Page {
+                    number: page,
+                    cells: BTreeMap::new(),
+                });
+            }
+        }
+        if let Some(current) = self.current.as_mut() {
+            current.cells.entry((line, column)).or_default().push(ch);
+        }
+        Ok(())
+    }
+
+    /// Write out the page currently being printed (if any).
+    pub fn finish(&mut self) -> Result<(), std::io::Error> {
+        match self.current.take() {
+            Some(page) => self.write(&page),
+            None => Ok(()),
+        }
+    }
+
+    fn write(&mut self, page: &Page) -> Result<(), std::io::Error> {
+        self.write_text(page)?;
+        if self.svg {
+            self.write_svg(page)?;
+        }
+        self.next_page = page.number + 1;
+        Ok(())
+    }
+
+    fn create(&self, page: &Page, extension: &str) -> Result<BufWriter<File>, std::io::Error> {
+        // Page numbers in file names start at 1, as they would on
+        // paper.
+        let path = self
+            .dir
+            .join(format!("page-{:04}.{}", page.number + 1, extension));
+        event!(Level::DEBUG, "writing printed page to {}", path.display());
+        Ok(BufWriter::new(File::create(&path)?))
+    }
+
+    fn write_text(&self, page: &Page) -> Result<(), std::io::Error> {
+        let mut w = self.create(page, "txt")?;
+        let mut lines: Vec<Vec<char>> = Vec::new();
+        for ((line, column), chars) in page.cells.iter() {
+            if lines.len() <= *line {
+                lines.resize(line + 1, Vec::new());
+            }
+            let text = &mut lines[*line];
+            if text.len() <= *column {
+                text.resize(column + 1, ' ');
+            }
+            // Plain text can't show overstrikes, so the last
+            // character printed wins.
+            if let Some(ch) = chars.last() {
+                text[*column] = char_to_show(ch);
+            }
+        }
+        for text in lines {
+            writeln!(w, "{}", text.into_iter().collect::<String>())?;
+        }
+        w.flush()
+    }
+
+    fn write_svg(&self, page: &Page) -> Result<(), std::io::Error> {
+        let mut w = self.create(page, "svg")?;
+        let width = (self.line_width as f64) * SVG_CHAR_WIDTH + (2 * SVG_MARGIN) as f64;
+        let height = self.lines_per_page * SVG_LINE_HEIGHT + 2 * SVG_MARGIN;
+        writeln!(
+            w,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" font-family="monospace" font-size="{SVG_FONT_SIZE}">"#
+        )?;
+        writeln!(w, r#"<rect width="100%" height="100%" fill="white"/>"#)?;
+        for ((line, column), chars) in page.cells.iter() {
+            let x = (*column as f64) * SVG_CHAR_WIDTH + SVG_MARGIN as f64;
+            let baseline = (line + 1) * SVG_LINE_HEIGHT + SVG_MARGIN;
+            for ch in chars {
+                let fill = match ch.attributes.colour {
+                    Colour::Black => "black",
+                    Colour::Red => "red",
+                };
+                let (y, size) = match ch.attributes.script {
+                    Script::Normal => (baseline, SVG_FONT_SIZE),
+                    Script::Super => (baseline - SVG_FONT_SIZE / 3, SVG_FONT_SIZE * 3 / 4),
+                    Script::Sub => (baseline + SVG_FONT_SIZE / 4, SVG_FONT_SIZE * 3 / 4),
+                };
+                let text = match char_to_show(ch) {
+                    '<' => "&lt;".to_string(),
+                    '>' => "&gt;".to_string(),
+                    '&' => "&amp;".to_string(),
+                    other => other.to_string(),
+                };
+                writeln!(
+                    w,
+                    r#"<text x="{x:.1}" y="{y}" font-size="{size}" fill="{fill}">{text}</text>"#
+                )?;
+            }
+        }
+        writeln!(w, "</svg>")?;
+        w.flush()
+    }
+}
+
+#[test]
+fn test_blank_pages_are_written() {
+    use base::charset::LincolnState;
+    let dir = std::env::temp_dir().join(format!("tx2-xerox-test-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).expect("should be able to create a temporary directory");
+    let described = |c: char| DescribedChar {
+        base_char: LincolnChar::UnicodeBaseChar(c),
+        unicode_representation: Some(c),
+        attributes: LincolnState::default(),
+        advance: true,
+        label_matches_unicode: true,
+    };
+    let mut printer = PagePrinter::new(dir.clone().into_os_string(), false, (66, 80));
+    // Nothing is printed on the first two pages or the fourth.
+    for (page, c) in [(2, 'A'), (4, 'B')] {
+        printer
+            .print(page, 0, 0, described(c))
+            .expect("printing should succeed");
+    }
+    printer.finish().expect("printing should succeed");
+    let read = |number: usize| std::fs::read_to_string(dir.join(format!("page-{number:04}.txt")));
+    let pages: Vec<String> = (1..=5)
+        .map(|number| read(number).expect("page should have been written"))
+        .collect();
+    assert_eq!(pages, vec!["", "", "A\n", "", "B\n"]);
+    assert!(read(6).is_err());
+    std::fs::remove_dir_all(&dir).expect("should be able to remove the temporary directory");
+}
@@ -37,6 +37,7 @@ pub enum AlarmKind {
     QSAL,
     IOSAL,
     MISAL,
+    OSAL,
     ROUNDTUITAL,
     DEFERLOOPAL,
     BUGAL,
@@ -50,6 +51,7 @@ impl Display for AlarmKind {
             AlarmKind::QSAL => "QSAL",
             AlarmKind::IOSAL => "IOSAL",
             AlarmKind::MISAL => "MISAL",
+            AlarmKind::OSAL => "OSAL",
             AlarmKind::ROUNDTUITAL => "ROUNDTUITAL",
             AlarmKind::DEFERLOOPAL => "DEFERLOOPAL",
             AlarmKind::BUGAL => "BUGAL",
@@ -67,13 +69,14 @@ impl AlarmKind {
         }
     }
 
-    pub const fn all_alarm_kinds() -> [AlarmKind; 8] {
+    pub const fn all_alarm_kinds() -> [AlarmKind; 9] {
         [
             AlarmKind::PSAL,
             AlarmKind::OCSAL,
             AlarmKind::QSAL,
             AlarmKind::IOSAL,
             AlarmKind::MISAL,
+            AlarmKind::OSAL,
             AlarmKind::ROUNDTUITAL,
             AlarmKind::DEFERLOOPAL,
             AlarmKind::BUGAL,
@@ -101,6 +104,7 @@ impl TryFrom<&str> for AlarmKind {
             "QSAL" => Ok(AlarmKind::QSAL),
             "IOSAL" => Ok(AlarmKind::IOSAL),
             "MISAL" => Ok(AlarmKind::MISAL),
+            "OSAL" => Ok(AlarmKind::OSAL),
             "ROUNDTUITAL" => Ok(AlarmKind::ROUNDTUITAL),
             "DEFERLOOPAL" => Ok(AlarmKind::DEFERLOOPAL),
             "BUGAL" => Ok(AlarmKind::BUGAL),
@@ -161,6 +165,12 @@ pub enum AlarmDetails {
     /// too many hold bits.
     MISAL { affected_unit: Unsigned6Bit },
 
+    /// Overflow Alarm.  Fires when an arithmetic instruction sets the
+    /// overflow indication of some subword of the Arithmetic
+    /// Element.  Programs which test for overflow with JOV will
+    /// normally mask this alarm (and it is initially masked).
+    OSAL(Instruction, String),
+
     // Alarms we probably should implement but have not:
     //
     // SYAL: Sync System Alarm
@@ -225,6 +235,7 @@ impl AlarmDetails {
                 message: _,
             } => AlarmKind::IOSAL,
             AlarmDetails::MISAL { affected_unit: _ } => AlarmKind::MISAL,
+            AlarmDetails::OSAL(_, _) => AlarmKind::OSAL,
             AlarmDetails::ROUNDTUITAL(_) => AlarmKind::ROUNDTUITAL,
             AlarmDetails::DEFERLOOPAL { address: _ } => AlarmKind::DEFERLOOPAL,
             AlarmDetails::BUGAL {
@@ -296,6 +307,15 @@ impl Display for AlarmDetails {
                 "MISAL: program too slow; missed data for unit {affected_unit:o}"
             ),
 
+            OSAL(inst, msg) => {
+                write!(
+                    f,
+                    "OSAL: arithmetic overflow during execution of instruction {:>012o}: {}",
+                    inst.bits(),
+                    msg
+                )
+            }
+
             BUGAL { instr, message } => {
                 if let Some(instruction) = instr.as_ref() {
                     if let Ok(symbolic) = SymbolicInstruction::try_from(instruction) {
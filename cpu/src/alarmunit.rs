@@ -8,8 +8,7 @@ use tracing::{event, Level};
 use super::alarm::{Alarm, AlarmDetails, AlarmKind, AlarmMaskability, Alarmer};
 use super::changelog::ChangeIndex;
 
-#[cfg(test)]
-use base::Unsigned6Bit;
+use base::prelude::*;
 
 #[derive(Debug, Serialize)]
 pub struct AlarmStatus {
@@ -25,17 +24,44 @@ pub struct AlarmStatus {
 /// - inactive: it's not happening
 /// - firing: it's happening and not masked (execution will stop)
 /// - active but not firing (visible on the console, execution continues)
+///
+/// In addition, the I/O alarms (IOSAL and MISAL) can be routed to
+/// the program instead of stopping the machine.  This is configured
+/// by connecting unit 41 (see [`AlarmUnit::set_program_alarm_mode`]).
+/// An unmasked alarm which is routed to the program does not fire;
+/// instead, the flag of sequence 41 is raised (see
+/// [`AlarmUnit::take_program_alarm_pending`]) and the details of the
+/// alarm are made available in the report word of unit 41.
 #[derive(Debug, Default)]
 pub struct AlarmUnit {
     panic_on_unmasked_alarm: bool,
     masked: BTreeSet<AlarmKind>,
     active: BTreeMap<AlarmKind, Alarm>,
     changes: ChangeIndex<AlarmKind>,
+    routed_to_program: BTreeSet<AlarmKind>,
+    program_alarm_mode: Unsigned12Bit,
+    program_alarm: Option<Alarm>,
+    program_alarm_pending: bool,
 }
 
 impl AlarmUnit {
+    /// When this bit is set in the connect mode of unit 41, IOSAL is
+    /// routed to the program.
+    pub const ROUTE_IOSAL: Unsigned12Bit = Unsigned12Bit::MAX.and(0o1_u16);
+
+    /// When this bit is set in the connect mode of unit 41, MISAL is
+    /// routed to the program.
+    pub const ROUTE_MISAL: Unsigned12Bit = Unsigned12Bit::MAX.and(0o2_u16);
+
+    /// Create an alarm unit in which only OSAL is masked.  Arithmetic
+    /// overflow is a normal occurrence in programs which test for it
+    /// with JOV, so we don't stop the machine for it unless the user
+    /// asks us to (by unmasking OSAL).
     pub fn new() -> AlarmUnit {
-        AlarmUnit::default()
+        AlarmUnit {
+            masked: BTreeSet::from([AlarmKind::OSAL]),
+            ..AlarmUnit::default()
+        }
     }
 
     fn status_for_alarm_kind(&self, kind: &AlarmKind) -> AlarmStatus {
@@ -106,6 +132,43 @@ impl AlarmUnit {
         }
     }
 
+    /// Select which I/O alarms are routed to the program (via
+    /// sequence 41) rather than stopping the machine.  `mode` is the
+    /// connect mode of unit 41; disconnecting unit 41 sets it to zero
+    /// (so that no alarms are routed to the program).
+    pub fn set_program_alarm_mode(&mut self, mode: Unsigned12Bit) {
+        self.program_alarm_mode = mode;
+        self.routed_to_program.clear();
+        if mode & Self::ROUTE_IOSAL != 0 {
+            self.routed_to_program.insert(AlarmKind::IOSAL);
+        }
+        if mode & Self::ROUTE_MISAL != 0 {
+            self.routed_to_program.insert(AlarmKind::MISAL);
+        }
+    }
+
+    pub fn program_alarm_mode(&self) -> Unsigned12Bit {
+        self.program_alarm_mode
+    }
+
+    /// Returns the most recent alarm which was routed to the program.
+    pub fn program_alarm(&self) -> Option<&Alarm> {
+        self.program_alarm.as_ref()
+    }
+
+    /// Indicate whether an alarm has been routed to the program
+    /// since the last call, meaning that the flag of sequence 41
+    /// should be raised.
+    pub fn take_program_alarm_pending(&mut self) -> bool {
+        std::mem::take(&mut self.program_alarm_pending)
+    }
+
+    /// Routing depends only on the connect mode of unit 41, not on
+    /// whether the alarm is masked.
+    fn is_routed_to_program(&self, alarm_instance: &Alarm) -> bool {
+        self.routed_to_program.contains(&alarm_instance.kind())
+    }
+
     fn is_masked(&self, alarm_instance: &Alarm) -> bool {
         let kind = alarm_instance.kind();
         match kind.maskable() {
@@ -163,7 +226,18 @@ impl AlarmUnit {
 impl Alarmer for AlarmUnit {
     fn fire_if_not_masked(&mut self, alarm_instance: Alarm) -> Result<(), Alarm> {
         self.changes.add(alarm_instance.kind());
-        self.set_active(alarm_instance)
+        if self.is_routed_to_program(&alarm_instance) {
+            event!(
+                Level::DEBUG,
+                "alarm {} is routed to the program via sequence 41",
+                alarm_instance
+            );
+            self.program_alarm = Some(alarm_instance);
+            self.program_alarm_pending = true;
+            Ok(())
+        } else {
+            self.set_active(alarm_instance)
+        }
     }
 
     fn always_fire(&mut self, alarm_instance: Alarm) -> Alarm {
@@ -235,6 +309,68 @@ fn maskable_alarms_are_not_masked_by_default() {
     assert!(alarm_unit.unmasked_alarm_active());
 }
 
+#[test]
+fn overflow_alarm_is_masked_by_default() {
+    let mut alarm_unit = AlarmUnit::new_with_panic(false);
+    let the_alarm = Alarm {
+        sequence: Some(Unsigned6Bit::ZERO),
+        details: AlarmDetails::OSAL(
+            base::instruction::Instruction::invalid(),
+            "overflow".to_string(),
+        ),
+    };
+    // OSAL is masked, so the alarm is active but does not fire.
+    assert!(alarm_unit.fire_if_not_masked(the_alarm.clone()).is_ok());
+    assert!(!alarm_unit.unmasked_alarm_active());
+    assert!(
+        alarm_unit
+            .get_status_of_alarm("OSAL")
+            .expect("OSAL should be known")
+            .active
+    );
+    // Once it is unmasked, it fires.
+    alarm_unit.unmask(AlarmKind::OSAL);
+    assert!(alarm_unit.unmasked_alarm_active());
+    assert!(alarm_unit.fire_if_not_masked(the_alarm).is_err());
+}
+
+#[test]
+fn routed_alarms_do_not_fire() {
+    let mut alarm_unit = AlarmUnit::new_with_panic(false);
+    let misal = Alarm {
+        sequence: None,
+        details: AlarmDetails::MISAL {
+            affected_unit: u6!(0o52),
+        },
+    };
+    alarm_unit.set_program_alarm_mode(AlarmUnit::ROUTE_MISAL);
+    assert!(alarm_unit.fire_if_not_masked(misal.clone()).is_ok());
+    assert!(!alarm_unit.unmasked_alarm_active());
+    assert!(alarm_unit.take_program_alarm_pending());
+    assert!(!alarm_unit.take_program_alarm_pending());
+    assert!(matches!(
+        alarm_unit.program_alarm(),
+        Some(Alarm {
+            sequence: None,
+            details: AlarmDetails::MISAL { affected_unit: _ }
+        })
+    ));
+    // IOSAL is not routed in this mode.
+    assert!(alarm_unit
+        .fire_if_not_masked(Alarm {
+            sequence: None,
+            details: AlarmDetails::IOSAL {
+                unit: u6!(0o52),
+                operand: None,
+                message: "not routed".to_string(),
+            },
+        })
+        .is_err());
+    // Disconnecting unit 41 stops MISAL being routed.
+    alarm_unit.set_program_alarm_mode(Unsigned12Bit::ZERO);
+    assert!(alarm_unit.fire_if_not_masked(misal).is_err());
+}
+
 // Alarm conditions we expect to use in the emulator but
 // which are not in use yet:
 // SYAL1,                       // Sync alarm 1 (see User Handbook page 5-21)
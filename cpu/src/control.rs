@@ -17,11 +17,14 @@ use std::time::Duration;
 
 use tracing::{event, span, Level};
 
+mod op_arith;
 mod op_configuration;
 mod op_index;
 mod op_io;
 mod op_jump;
 mod op_loadstore;
+mod op_logical;
+mod op_shift;
 #[cfg(test)]
 mod tests;
 mod timing;
@@ -42,6 +45,7 @@ use super::io::DeviceManager;
 use super::memory::{self, ExtraBits, MemoryMapped, MemoryOpFailure, MemoryUnit, MetaBitChange};
 use super::*;
 
+use op_arith::OverflowLatch;
 use trap::TrapCircuit;
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
@@ -291,12 +295,18 @@ pub struct ControlRegisters {
     /// integers.
     index_regs: [Signed18Bit; 0o100], // AKA the X memory
     f_memory: [SystemConfiguration; 32], // the F memory
+    /// Overflow indications for the subwords of the Arithmetic
+    /// Element, set by arithmetic instructions.
+    overflow: OverflowLatch,
     flags: SequenceFlags,
     current_sequence_is_runnable: bool,
-    // TODO: we may be able to eliminate prev_hold by moving the logic
-    // that's currently at the beginning of fetch_instruction() so
-    // that it occurs at the end of execute_instruction() instead.
-    // See the comment at the top of fetch_instruction().
+    /// The hold bit of the most recently completed instruction.
+    /// This is set at the end of execute_instruction() and, while
+    /// it is set, select_sequence() will not change sequence.
+    // TODO: select_sequence() is also called at the start of
+    // execute_instruction(), when N no longer contains the previous
+    // instruction (for example after a TSD ends in dismiss-and-wait).
+    // If that call could be eliminated, so could prev_hold.
     prev_hold: bool,
 }
 
@@ -320,6 +330,7 @@ impl ControlRegisters {
             k: None, // not 0, so that we can recognise CODABO.
             index_regs: [Signed18Bit::default(); 0o100],
             f_memory: fmem,
+            overflow: OverflowLatch::default(),
             flags: SequenceFlags::new(),
             current_sequence_is_runnable: false,
             prev_hold: false,
@@ -373,7 +384,7 @@ impl ControlRegisters {
     fn get_f_mem(&self, n: Unsigned5Bit) -> SystemConfiguration {
         // Use u8::from in order to be able to compare an Unsigned5Bit.
         #![allow(clippy::cmp_owned)]
-        assert!(u8::from(n) < 0o37_u8);
+        assert!(u8::from(n) <= 0o37_u8);
         assert_eq!(self.f_memory[0], SystemConfiguration::zero());
         let pos: usize = n.into();
         self.f_memory[pos]
@@ -441,6 +452,9 @@ pub struct ControlUnit {
     regs: ControlRegisters,
     trap: TrapCircuit,
     alarm_unit: AlarmUnit,
+    /// When set, opcode 2 is executed as XEQ (see
+    /// [`ControlUnit::set_xeq_enabled`]).
+    xeq_enabled: bool,
 }
 
 fn sign_extend_index_value(index_val: &Signed18Bit) -> Unsigned36Bit {
@@ -457,6 +471,9 @@ pub(crate) struct OpcodeResult {
     program_counter_change: Option<ProgramCounterChange>,
     poll_order_change: Option<SequenceNumber>,
     output: Option<OutputEvent>,
+    /// For instructions whose execution time depends on their
+    /// operands, the work they did.
+    arithmetic_work: Option<timing::ArithmeticWork>,
 }
 
 #[test]
@@ -488,6 +505,82 @@ fn please_poll_soon(ctx: &Context, devices: &mut DeviceManager, seq: SequenceNum
     devices.update_poll_time(ctx, seq);
 }
 
+/// Execute the instruction in the N register, whose opcode is
+/// `opcode`.  `prev_program_counter` is the location from which the
+/// instruction was fetched.
+fn execute_opcode(
+    ctx: &Context,
+    prev_program_counter: Address,
+    opcode: &Opcode,
+    control: &mut ControlUnit,
+    devices: &mut DeviceManager,
+    mem: &mut MemoryUnit,
+) -> Result<OpcodeResult, Alarm> {
+    match opcode {
+        Opcode::Xeq => match control.load_xeq_target(ctx, mem)? {
+            Some(target_opcode) => execute_opcode(
+                ctx,
+                prev_program_counter,
+                &target_opcode,
+                control,
+                devices,
+                mem,
+            ),
+            None => Ok(OpcodeResult::default()),
+        },
+        Opcode::Aux => control.op_aux(ctx, mem),
+        Opcode::Lda => control.op_lda(ctx, mem),
+        Opcode::Ldb => control.op_ldb(ctx, mem),
+        Opcode::Ldc => control.op_ldc(ctx, mem),
+        Opcode::Ldd => control.op_ldd(ctx, mem),
+        Opcode::Lde => control.op_lde(ctx, mem),
+        Opcode::Sta => control.op_sta(ctx, mem),
+        Opcode::Stb => control.op_stb(ctx, mem),
+        Opcode::Stc => control.op_stc(ctx, mem),
+        Opcode::Std => control.op_std(ctx, mem),
+        Opcode::Ste => control.op_ste(ctx, mem),
+        Opcode::Rsx => control.op_rsx(ctx, mem),
+        Opcode::Skx => control.op_skx(ctx),
+        Opcode::Dpx => control.op_dpx(ctx, mem),
+        Opcode::Exx => control.op_exx(ctx, mem),
+        Opcode::Adx => control.op_adx(ctx, mem),
+        Opcode::Jmp => control.op_jmp(ctx, mem),
+        Opcode::Jpx => control.op_jpx(ctx, mem),
+        Opcode::Jnx => control.op_jnx(ctx, mem),
+        Opcode::Skm => control.op_skm(ctx, mem),
+        Opcode::Spg => control.op_spg(ctx, mem),
+        Opcode::Ios => control.op_ios(ctx, mem, devices),
+        Opcode::Tsd => control.op_tsd(ctx, devices, prev_program_counter, mem),
+        Opcode::Sed => control.op_sed(ctx, mem),
+        Opcode::Add => control.op_add(ctx, mem),
+        Opcode::Sub => control.op_sub(ctx, mem),
+        Opcode::Mul => control.op_mul(ctx, mem),
+        Opcode::Div => control.op_div(ctx, mem),
+        Opcode::Cya => control.op_cya(ctx, mem),
+        Opcode::Cyb => control.op_cyb(ctx, mem),
+        Opcode::Cab => control.op_cab(ctx, mem),
+        Opcode::Noa => control.op_noa(ctx, mem),
+        Opcode::Nab => control.op_nab(ctx, mem),
+        Opcode::Sca => control.op_sca(ctx, mem),
+        Opcode::Scb => control.op_scb(ctx, mem),
+        Opcode::Sab => control.op_sab(ctx, mem),
+        Opcode::Ite => control.op_ite(ctx, mem),
+        Opcode::Ita => control.op_ita(ctx, mem),
+        Opcode::Una => control.op_una(ctx, mem),
+        Opcode::Com => control.op_com(ctx, mem),
+        Opcode::Dsa => control.op_dsa(ctx, mem),
+        Opcode::Exa => control.op_exa(ctx, mem),
+        Opcode::Ins => control.op_ins(ctx, mem),
+        Opcode::Tly => control.op_tly(ctx, mem),
+        Opcode::Jpa => control.op_jpa(ctx, mem),
+        Opcode::Jna => control.op_jna(ctx, mem),
+        Opcode::Jov => control.op_jov(ctx, mem),
+        Opcode::Spf => control.op_spf(ctx, mem),
+        Opcode::Flf => control.op_flf(ctx, mem),
+        Opcode::Flg => control.op_flg(ctx, mem),
+    }
+}
+
 impl ControlUnit {
     pub fn new(
         panic_on_unmasked_alarm: PanicOnUnmaskedAlarm,
@@ -500,6 +593,7 @@ impl ControlUnit {
                 PanicOnUnmaskedAlarm::No => false,
                 PanicOnUnmaskedAlarm::Yes => true,
             }),
+            xeq_enabled: false,
         }
     }
 
@@ -540,6 +634,16 @@ impl ControlUnit {
         self.trap.set_metabits_disabled(disable);
     }
 
+    /// Enable or disable experimental support for the XEQ
+    /// instruction (opcode 2).  We don't know whether the TX-2
+    /// actually implemented XEQ (see OPEN-QUESTIONS.md), so by
+    /// default opcode 2 is treated as an invalid opcode (raising
+    /// OCSAL).  When enabled, XEQ executes the instruction at its
+    /// operand address without changing P.
+    pub fn set_xeq_enabled(&mut self, enable: bool) {
+        self.xeq_enabled = enable;
+    }
+
     /// There are actually 9 different CODABO buttons (see page 5-18
     /// of the User Guide).  There are also 9 corresponding RESET
     /// buttons.  Each RESET button has a corresponding CODABO button.
@@ -690,6 +794,26 @@ impl ControlUnit {
         Unsigned6Bit::try_from(0o42).unwrap()
     }
 
+    fn io_alarm_seq() -> Unsigned6Bit {
+        Unsigned6Bit::try_from(0o41).unwrap()
+    }
+
+    /// If an I/O alarm has been routed to the program (instead of
+    /// stopping the machine), raise the flag of sequence 41.  Returns
+    /// true if the flag was raised.
+    fn raise_flag_for_program_alarm(&mut self) -> bool {
+        if self.alarm_unit.take_program_alarm_pending() {
+            event!(
+                Level::DEBUG,
+                "an I/O alarm was routed to the program; raising flag of sequence 41"
+            );
+            self.regs.flags.raise(&Self::io_alarm_seq());
+            true
+        } else {
+            false
+        }
+    }
+
     fn raise_trap(&mut self) {
         self.regs.flags.raise(&Self::trap_seq());
     }
@@ -1011,16 +1135,25 @@ impl ControlUnit {
         // alarm was not an error return from the poll() method,
         // because we needed to ensure that all flag raised were
         // processed.
+        //
+        // Such an alarm is subject to routing to sequence 41 and to
+        // masking in the same way as alarms raised by instructions,
+        // so a masked alarm which is not routed to the program does
+        // not stop the machine.
         if let Some(active) = alarm {
             event!(
                 Level::INFO,
                 "poll_hardware: an alarm is active: {:?}",
                 active
             );
-            Err(active)
-        } else {
-            Ok((run_mode, next_poll))
+            self.alarm_unit.fire_if_not_masked(active)?;
+        }
+        // The alarm (either from polling, or from a unit's status
+        // report above) may have been routed to sequence 41.
+        if self.raise_flag_for_program_alarm() {
+            run_mode = RunMode::Running;
         }
+        Ok((run_mode, next_poll))
     }
 
     fn update_n_register(&mut self, instruction_word: Unsigned36Bit) -> Result<(), Alarm> {
@@ -1036,6 +1169,44 @@ impl ControlUnit {
         }
     }
 
+    /// Performs the first part of the (experimental) XEQ
+    /// instruction, loading the N register with the instruction at
+    /// the operand address.  P is not changed, so once the loaded
+    /// instruction has been executed, execution continues after the
+    /// XEQ instruction (unless the executed instruction jumps or
+    /// skips).  Returns the opcode of the instruction to execute, or
+    /// `None` if it is invalid but OCSAL is masked.
+    fn load_xeq_target(
+        &mut self,
+        ctx: &Context,
+        mem: &mut MemoryUnit,
+    ) -> Result<Option<Opcode>, Alarm> {
+        if !self.xeq_enabled {
+            return Err(self.invalid_opcode_alarm());
+        }
+        let target: Address = self.operand_address_with_optional_defer_and_index(ctx, mem)?;
+        let (word, _extra) =
+            self.fetch_operand_from_address_without_exchange(ctx, mem, &target, &UpdateE::No)?;
+        event!(
+            Level::TRACE,
+            "XEQ: executing instruction {word:>012o} from {target:o}"
+        );
+        self.update_n_register(word)?;
+        match self.regs.n_sym.as_ref().map(|sym| sym.opcode()) {
+            Some(Opcode::Xeq) => {
+                // We have no idea whether the TX-2 allowed this, and
+                // a chain of XEQ instructions can loop forever.
+                Err(self.alarm_unit.always_fire(Alarm {
+                    sequence: self.regs.k,
+                    details: AlarmDetails::ROUNDTUITAL(
+                        "XEQ of an XEQ instruction is not implemented".to_string(),
+                    ),
+                }))
+            }
+            other => Ok(other),
+        }
+    }
+
     fn invalid_opcode_alarm(&self) -> Alarm {
         Alarm {
             sequence: self.regs.k,
@@ -1079,47 +1250,6 @@ impl ControlUnit {
             return Ok((0, RunMode::InLimbo, None));
         }
 
-        fn execute(
-            ctx: &Context,
-            prev_program_counter: Address,
-            opcode: &Opcode,
-            control: &mut ControlUnit,
-            devices: &mut DeviceManager,
-            mem: &mut MemoryUnit,
-        ) -> Result<OpcodeResult, Alarm> {
-            match opcode {
-                Opcode::Aux => control.op_aux(ctx, mem),
-                Opcode::Lda => control.op_lda(ctx, mem),
-                Opcode::Ldb => control.op_ldb(ctx, mem),
-                Opcode::Ldc => control.op_ldc(ctx, mem),
-                Opcode::Ldd => control.op_ldd(ctx, mem),
-                Opcode::Lde => control.op_lde(ctx, mem),
-                Opcode::Sta => control.op_sta(ctx, mem),
-                Opcode::Stb => control.op_stb(ctx, mem),
-                Opcode::Stc => control.op_stc(ctx, mem),
-                Opcode::Std => control.op_std(ctx, mem),
-                Opcode::Ste => control.op_ste(ctx, mem),
-                Opcode::Rsx => control.op_rsx(ctx, mem),
-                Opcode::Skx => control.op_skx(ctx),
-                Opcode::Dpx => control.op_dpx(ctx, mem),
-                Opcode::Jmp => control.op_jmp(ctx, mem),
-                Opcode::Jpx => control.op_jpx(ctx, mem),
-                Opcode::Jnx => control.op_jnx(ctx, mem),
-                Opcode::Skm => control.op_skm(ctx, mem),
-                Opcode::Spg => control.op_spg(ctx, mem),
-                Opcode::Ios => control.op_ios(ctx, mem, devices),
-                Opcode::Tsd => control.op_tsd(ctx, devices, prev_program_counter, mem),
-                Opcode::Sed => control.op_sed(ctx, mem),
-                _ => Err(Alarm {
-                    sequence: control.regs.k,
-                    details: AlarmDetails::ROUNDTUITAL(format!(
-                        "The emulator does not yet implement opcode {}",
-                        opcode,
-                    )),
-                }),
-            }
-        }
-
         let seq_desc = match self.regs.k {
             None => "none".to_string(),
             Some(n) => format!("{:02o}", n),
@@ -1140,11 +1270,12 @@ impl ControlUnit {
         let p = self.regs.p;
         self.set_program_counter(ProgramCounterChange::CounterUpdate);
 
-        let elapsed_time = self.estimate_execute_time_ns(&self.regs.n);
+        let mut elapsed_time = self.estimate_execute_time_ns(&self.regs.n);
 
         let result: Result<Option<OutputEvent>, (Alarm, Address)> =
             if let Some(sym) = self.regs.n_sym.as_ref() {
                 let inst = sym.to_string();
+                let opcode = sym.opcode();
                 let span = span!(Level::INFO,
                                  "xop",
                                  seq=%seq_desc,
@@ -1152,8 +1283,11 @@ impl ControlUnit {
                                  op=%sym.opcode());
                 let _enter = span.enter();
                 event!(Level::TRACE, "executing instruction {}", &sym);
-                match execute(ctx, p, &sym.opcode(), self, devices, mem) {
+                match execute_opcode(ctx, p, &opcode, self, devices, mem) {
                     Ok(opcode_result) => {
+                        if let Some(work) = opcode_result.arithmetic_work.as_ref() {
+                            elapsed_time += timing::estimate_arithmetic_work_ns(opcode, work);
+                        }
                         event!(
                             Level::TRACE,
                             "opcode_result.poll_order_change={:?}",
@@ -1228,6 +1362,13 @@ impl ControlUnit {
         // instruction should be followed by a change of sequence.
         match result {
             Ok(maybe_output) => {
+                // If this instruction was held (for example LDE or
+                // ITE, which are normally held, or any instruction
+                // whose hold bit is set) we must not change sequence
+                // before the next instruction.  See section 4-3.5 of
+                // the User Handbook.
+                self.regs.prev_hold = self.regs.n.is_held();
+                self.raise_flag_for_program_alarm();
                 let new_mode: RunMode = self.select_sequence(mem);
                 Ok((elapsed_time, new_mode, maybe_output))
             }
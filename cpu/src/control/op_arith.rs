@@ -0,0 +1,1263 @@
+//! Implementations of "Arithmetic Class" opcodes
+//! - ADD: [`ControlUnit::op_add`]
+//! - SUB: [`ControlUnit::op_sub`]
+//! - MUL: [`ControlUnit::op_mul`]
+//! - DIV: [`ControlUnit::op_div`]
+//! - TLY: [`ControlUnit::op_tly`]
+//!
+//! TX-2 arithmetic is one's complement.  The subword form of the
+//! current system configuration determines how the 36-bit word is
+//! fractured into subwords; each subword is operated on separately
+//! (carries do not propagate between subwords) and each subword has
+//! its own overflow indication.  Only the active quarters of the A
+//! register are changed.
+
+use tracing::{event, Level};
+
+use base::prelude::*;
+
+use super::alarm::{Alarm, AlarmDetails};
+use super::context::Context;
+use super::control::{ControlUnit, OpcodeResult, UpdateE};
+use super::exchanger::{QuarterActivity, SubwordForm, SystemConfiguration};
+use super::memory::MemoryUnit;
+use super::timing::ArithmeticWork;
+
+/// Records which subwords of the Arithmetic Element have overflowed.
+/// Quarters are numbered from 0 (as in `QuarterActivity`); when a
+/// subword overflows, the bits of all the quarters making up that
+/// subword are set.  The latch is set by arithmetic instructions but
+/// is never cleared by them; it is tested and cleared by JOV.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct OverflowLatch(u8);
+
+impl OverflowLatch {
+    pub(crate) fn set(&mut self, overflowed: OverflowLatch) {
+        self.0 |= overflowed.0;
+    }
+
+    /// Record an overflow in `subword`.
+    pub(crate) fn mark(&mut self, subword: &Subword) {
+        self.0 |= subword.quarters;
+    }
+
+    pub(crate) fn is_set(&self) -> bool {
+        self.0 != 0
+    }
+
+    /// Determine whether any of `subwords` has overflowed, clearing
+    /// the overflow indications of those subwords.
+    pub(crate) fn test_and_clear(&mut self, subwords: &[Subword]) -> bool {
+        let quarters: u8 = subwords.iter().fold(0, |acc, sw| acc | sw.quarters);
+        let overflowed = self.0 & quarters != 0;
+        self.0 &= !quarters;
+        overflowed
+    }
+}
+
+/// Returns the quarters (as a bit mask in which quarter 1 is the
+/// least significant bit) which make up each subword for the subword
+/// form `form`.
+fn subword_quarters(form: &SubwordForm) -> &'static [u8] {
+    match form {
+        SubwordForm::FullWord => &[0b1111],
+        SubwordForm::Halves => &[0b1100, 0b0011],
+        SubwordForm::ThreeOne => &[0b1110, 0b0001],
+        SubwordForm::Quarters => &[0b1000, 0b0100, 0b0010, 0b0001],
+    }
+}
+
+/// Convert a `QuarterActivity` value into a bit mask of quarters (in
+/// which quarter 1 is the least significant bit).
+fn active_quarter_bits(activity: &QuarterActivity) -> u8 {
+    (0..4_u8)
+        .filter(|q| activity.is_active(q))
+        .fold(0, |acc, q| acc | (1 << q))
+}
+
+/// Convert a bit mask of quarters into a bit mask of the
+/// corresponding bits of a 36-bit word.
+fn quarters_to_bit_mask(quarters: u8) -> u64 {
+    (0..4_u32)
+        .filter(|q| quarters & (1 << q) != 0)
+        .fold(0, |acc, q| acc | (0o777 << (q * 9)))
+}
+
+/// Describes a subword (of which at least one quarter is active)
+/// within a 36-bit word.
+#[derive(Debug)]
+pub(crate) struct Subword {
+    /// The quarters making up the subword (quarter 1 is the least
+    /// significant bit).
+    pub(crate) quarters: u8,
+    /// The position of the least significant bit of the subword.
+    pub(crate) shift: u32,
+    /// The width of the subword in bits.
+    pub(crate) width: u32,
+    /// The bits of the 36-bit word which are both in this subword
+    /// and active.
+    pub(crate) active_bits: u64,
+}
+
+impl Subword {
+    pub(crate) fn mask(&self) -> u64 {
+        (1 << self.width) - 1
+    }
+
+    /// Extract the value of this subword from `word`.
+    pub(crate) fn extract(&self, word: u64) -> u64 {
+        (word >> self.shift) & self.mask()
+    }
+
+    /// Replace the active bits of this subword within `word` by the
+    /// corresponding bits of `value`.
+    pub(crate) fn insert(&self, word: u64, value: u64) -> u64 {
+        (word & !self.active_bits) | ((value << self.shift) & self.active_bits)
+    }
+}
+
+/// Returns the subwords of the system configuration `cfg` which
+/// have at least one active quarter.  Subwords having no active
+/// quarters are unaffected by arithmetic (and shift) instructions.
+pub(crate) fn active_subwords(cfg: &SystemConfiguration) -> Vec<Subword> {
+    let active = active_quarter_bits(&cfg.active_quarters());
+    subword_quarters(&cfg.subword_form())
+        .iter()
+        .filter(|quarters| *quarters & active != 0)
+        .map(|quarters| Subword {
+            quarters: *quarters,
+            shift: quarters.trailing_zeros() * 9,
+            width: quarters.count_ones() * 9,
+            active_bits: quarters_to_bit_mask(quarters & active),
+        })
+        .collect()
+}
+
+/// Split the `width`-bit one's complement value `value` into its
+/// sign (true for negative) and magnitude.
+pub(crate) fn split_sign(value: u64, width: u32) -> (bool, u64) {
+    let mask: u64 = (1 << width) - 1;
+    if value & (1 << (width - 1)) != 0 {
+        (true, !value & mask)
+    } else {
+        (false, value)
+    }
+}
+
+/// Combine a sign and a magnitude into a `width`-bit one's complement
+/// value.  The magnitude must fit into `width - 1` bits.
+pub(crate) fn apply_sign(negative: bool, magnitude: u64, width: u32) -> u64 {
+    let mask: u64 = (1 << width) - 1;
+    assert_eq!(magnitude & !(mask >> 1), 0, "magnitude is too large");
+    if negative {
+        !magnitude & mask
+    } else {
+        magnitude
+    }
+}
+
+/// Add two one's complement values of width `width` bits, returning
+/// the sum and an indication of whether the addition overflowed.
+/// A carry out of the top bit is added back in at the bottom (this
+/// is the "end-around carry").
+pub(crate) fn ones_complement_add(a: u64, b: u64, width: u32) -> (u64, bool) {
+    let mask: u64 = (1 << width) - 1;
+    let sign: u64 = 1 << (width - 1);
+    let mut sum = a + b;
+    if sum > mask {
+        // The end-around carry cannot itself cause a further carry,
+        // since a + b <= 2 * mask.
+        sum = (sum & mask) + 1;
+    }
+    // Overflow can only occur when the signs of the inputs are the
+    // same, and is indicated by the sign of the result differing
+    // from them.
+    let overflow = (a & sign) == (b & sign) && (sum & sign) != (a & sign);
+    (sum, overflow)
+}
+
+/// Add `addend` to `augend`, subword by subword, as specified by
+/// the system configuration `cfg`.  Inactive quarters of the result
+/// are taken from `augend`.  Returns the result and the overflow
+/// indications for any active subwords which overflowed.
+fn add_by_subwords(
+    cfg: &SystemConfiguration,
+    augend: Unsigned36Bit,
+    addend: Unsigned36Bit,
+) -> (Unsigned36Bit, OverflowLatch) {
+    let augend = u64::from(augend);
+    let addend = u64::from(addend);
+    let mut result: u64 = augend;
+    let mut overflow = OverflowLatch::default();
+    for subword in active_subwords(cfg) {
+        let (sum, overflowed) = ones_complement_add(
+            subword.extract(augend),
+            subword.extract(addend),
+            subword.width,
+        );
+        if overflowed {
+            overflow.mark(&subword);
+        }
+        result = subword.insert(result, sum);
+    }
+    (to_word(result), overflow)
+}
+
+/// Multiply `multiplicand` by `multiplier`, subword by subword, as
+/// specified by the system configuration `cfg`.  Returns the
+/// (A, B) pair holding the product.
+///
+/// The operands are treated as one's complement fractions.  So for
+/// each subword of width w, the 2(w-1)-bit magnitude of the product
+/// is split into a more significant part (placed in the subword of
+/// A) and a less significant part (placed in the subword of B).  The
+/// sign of the product is given to both parts.  A consequence of this
+/// is that the product of two small integers appears in B.
+///
+/// Inactive quarters of A and B are unchanged.
+fn multiply_by_subwords(
+    cfg: &SystemConfiguration,
+    multiplicand: Unsigned36Bit,
+    multiplier: Unsigned36Bit,
+    b: Unsigned36Bit,
+) -> (Unsigned36Bit, Unsigned36Bit) {
+    let multiplicand = u64::from(multiplicand);
+    let multiplier = u64::from(multiplier);
+    let mut a_result: u64 = multiplicand;
+    let mut b_result: u64 = u64::from(b);
+    for subword in active_subwords(cfg) {
+        let w = subword.width;
+        let (x_negative, x) = split_sign(subword.extract(multiplicand), w);
+        let (y_negative, y) = split_sign(subword.extract(multiplier), w);
+        let negative = x_negative != y_negative;
+        let product: u128 = u128::from(x) * u128::from(y);
+        let low_mask: u128 = (1 << (w - 1)) - 1;
+        let high = u64::try_from(product >> (w - 1)).expect("product should fit (this is a bug)");
+        let low = u64::try_from(product & low_mask).expect("product should fit (this is a bug)");
+        a_result = subword.insert(a_result, apply_sign(negative, high, w));
+        b_result = subword.insert(b_result, apply_sign(negative, low, w));
+    }
+    (to_word(a_result), to_word(b_result))
+}
+
+/// Divide the value in AB by `divisor`, subword by subword, as
+/// specified by the system configuration `cfg`.  This is the
+/// inverse of [`multiply_by_subwords`]: the sign of the dividend is
+/// the sign of its A part (the sign bit of the B part is ignored).
+/// Returns the (A, B) pair holding the quotient and remainder
+/// respectively, and the overflow indications for any subwords for
+/// which the quotient could not be represented (which includes
+/// division by zero).  Subwords which overflow are left unchanged.
+/// The remainder has the same sign as the dividend.
+fn divide_by_subwords(
+    cfg: &SystemConfiguration,
+    a: Unsigned36Bit,
+    b: Unsigned36Bit,
+    divisor: Unsigned36Bit,
+) -> (Unsigned36Bit, Unsigned36Bit, OverflowLatch) {
+    let a = u64::from(a);
+    let b = u64::from(b);
+    let divisor = u64::from(divisor);
+    let mut a_result: u64 = a;
+    let mut b_result: u64 = b;
+    let mut overflow = OverflowLatch::default();
+    for subword in active_subwords(cfg) {
+        let w = subword.width;
+        let low_mask: u64 = (1 << (w - 1)) - 1;
+        let (dividend_negative, high) = split_sign(subword.extract(a), w);
+        let low = if dividend_negative {
+            !subword.extract(b) & low_mask
+        } else {
+            subword.extract(b) & low_mask
+        };
+        let (divisor_negative, d) = split_sign(subword.extract(divisor), w);
+        if high >= d {
+            // The quotient would not fit in the subword.
+            overflow.mark(&subword);
+            continue;
+        }
+        let dividend: u128 = (u128::from(high) << (w - 1)) | u128::from(low);
+        let d = u128::from(d);
+        let quotient = u64::try_from(dividend / d).expect("quotient should fit (this is a bug)");
+        let remainder = u64::try_from(dividend % d).expect("remainder should fit (this is a bug)");
+        a_result = subword.insert(
+            a_result,
+            apply_sign(dividend_negative != divisor_negative, quotient, w),
+        );
+        b_result = subword.insert(b_result, apply_sign(dividend_negative, remainder, w));
+    }
+    (to_word(a_result), to_word(b_result), overflow)
+}
+
+/// Returns the work done by a MUL with multiplier `multiplier`: one
+/// step for each magnitude bit of the subword and one addition for
+/// each of those bits which is a one.
+fn multiply_work(cfg: &SystemConfiguration, multiplier: Unsigned36Bit) -> ArithmeticWork {
+    let multiplier = u64::from(multiplier);
+    active_subwords(cfg)
+        .iter()
+        .map(|subword| {
+            let (_, magnitude) = split_sign(subword.extract(multiplier), subword.width);
+            ArithmeticWork {
+                steps: subword.width - 1,
+                additions: magnitude.count_ones(),
+            }
+        })
+        .max_by_key(|work| work.steps + work.additions)
+        .unwrap_or_default()
+}
+
+/// Returns the work done by a DIV: one step for each magnitude bit
+/// of the subword.  Subwords which overflowed are not divided.
+fn divide_work(cfg: &SystemConfiguration, overflow: &OverflowLatch) -> ArithmeticWork {
+    active_subwords(cfg)
+        .iter()
+        .filter(|subword| overflow.0 & subword.quarters == 0)
+        .map(|subword| ArithmeticWork {
+            steps: subword.width - 1,
+            additions: 0,
+        })
+        .max_by_key(|work| work.steps)
+        .unwrap_or_default()
+}
+
+/// Returns the work done by a TLY: one step for each active bit
+/// counted in the subword.
+fn tally_work(cfg: &SystemConfiguration) -> ArithmeticWork {
+    active_subwords(cfg)
+        .iter()
+        .map(|subword| ArithmeticWork {
+            steps: subword.active_bits.count_ones(),
+            additions: 0,
+        })
+        .max_by_key(|work| work.steps)
+        .unwrap_or_default()
+}
+
+pub(crate) fn to_word(value: u64) -> Unsigned36Bit {
+    Unsigned36Bit::try_from(value).expect("result should be in range (this is a bug)")
+}
+
+/// Count the ones in each active subword of `operand` (considering
+/// only its active quarters), as specified by the system
+/// configuration `cfg`, placing each count in the corresponding
+/// subword of the result.  Inactive quarters of the result are taken
+/// from `a`.
+fn tally_by_subwords(
+    cfg: &SystemConfiguration,
+    a: Unsigned36Bit,
+    operand: Unsigned36Bit,
+) -> Unsigned36Bit {
+    let operand = u64::from(operand);
+    let result = active_subwords(cfg)
+        .iter()
+        .fold(u64::from(a), |result, subword| {
+            let ones = (operand & subword.active_bits).count_ones();
+            subword.insert(result, u64::from(ones))
+        });
+    to_word(result)
+}
+
+/// ## "Arithmetic Class" opcodes
+///
+/// - ADD: [`ControlUnit::op_add`]
+/// - SUB: [`ControlUnit::op_sub`]
+/// - MUL: [`ControlUnit::op_mul`]
+/// - DIV: [`ControlUnit::op_div`]
+/// - TLY: [`ControlUnit::op_tly`]
+impl ControlUnit {
+    /// Implements the ADD instruction (Opcode 067).  The
+    /// (exchanged) operand is added to the A register.
+    pub(crate) fn op_add(
+        &mut self,
+        ctx: &Context,
+        mem: &mut MemoryUnit,
+    ) -> Result<OpcodeResult, Alarm> {
+        let operand = self.fetch_arithmetic_operand(ctx, mem)?;
+        self.add_to_a_register(mem, operand)?;
+        Ok(OpcodeResult::default())
+    }
+
+    /// Implements the SUB instruction (Opcode 077).  The
+    /// (exchanged) operand is subtracted from the A register.
+    ///
+    /// Subtraction is performed by adding the one's complement of
+    /// the operand.  This means that subtracting a value from itself
+    /// yields minus zero.  We don't yet know whether the TX-2
+    /// arithmetic element did the same.
+    pub(crate) fn op_sub(
+        &mut self,
+        ctx: &Context,
+        mem: &mut MemoryUnit,
+    ) -> Result<OpcodeResult, Alarm> {
+        let operand = self.fetch_arithmetic_operand(ctx, mem)?;
+        self.add_to_a_register(mem, !operand)?;
+        Ok(OpcodeResult::default())
+    }
+
+    /// Implements the MUL instruction (Opcode 076).  The A register
+    /// is multiplied by the (exchanged) operand, leaving the product
+    /// in A and B.  See [`multiply_by_subwords`] for the layout of the
+    /// product.
+    pub(crate) fn op_mul(
+        &mut self,
+        ctx: &Context,
+        mem: &mut MemoryUnit,
+    ) -> Result<OpcodeResult, Alarm> {
+        let operand = self.fetch_arithmetic_operand(ctx, mem)?;
+        let cfg = self.get_config();
+        let (a, b) =
+            multiply_by_subwords(&cfg, mem.get_a_register(), operand, mem.get_b_register());
+        mem.set_a_register(a);
+        mem.set_b_register(b);
+        Ok(OpcodeResult {
+            arithmetic_work: Some(multiply_work(&cfg, operand)),
+            ..Default::default()
+        })
+    }
+
+    /// Implements the DIV instruction (Opcode 075).  The value in AB
+    /// is divided by the (exchanged) operand, leaving the quotient in
+    /// A and the remainder in B.  When the quotient would not fit in
+    /// A (including division by zero) the overflow indication is set
+    /// and A and B are unchanged.
+    pub(crate) fn op_div(
+        &mut self,
+        ctx: &Context,
+        mem: &mut MemoryUnit,
+    ) -> Result<OpcodeResult, Alarm> {
+        let operand = self.fetch_arithmetic_operand(ctx, mem)?;
+        let cfg = self.get_config();
+        let (a, b, overflow) =
+            divide_by_subwords(&cfg, mem.get_a_register(), mem.get_b_register(), operand);
+        if overflow != OverflowLatch::default() {
+            event!(Level::DEBUG, "divide overflow (overflow {:?})", overflow);
+        }
+        mem.set_a_register(a);
+        mem.set_b_register(b);
+        self.record_overflow(overflow)?;
+        Ok(OpcodeResult {
+            arithmetic_work: Some(divide_work(&cfg, &overflow)),
+            ..Default::default()
+        })
+    }
+
+    /// Implements the TLY instruction (Opcode 074).  For each active
+    /// subword, the number of ones in the active quarters of the
+    /// (exchanged) operand is placed in the corresponding subword of
+    /// A.  Bits supplied by sign extension are not counted.
+    pub(crate) fn op_tly(
+        &mut self,
+        ctx: &Context,
+        mem: &mut MemoryUnit,
+    ) -> Result<OpcodeResult, Alarm> {
+        let operand = self.fetch_arithmetic_operand(ctx, mem)?;
+        let a = mem.get_a_register();
+        let cfg = self.get_config();
+        let tally = tally_by_subwords(&cfg, a, operand);
+        event!(
+            Level::TRACE,
+            "TLY: tally of {operand:o} is {tally:o} (A was {a:o})"
+        );
+        mem.set_a_register(tally);
+        Ok(OpcodeResult {
+            arithmetic_work: Some(tally_work(&cfg)),
+            ..Default::default()
+        })
+    }
+
+    /// Fetch the operand of an arithmetic instruction, passing it
+    /// through the exchange element.  Inactive quarters of the
+    /// result are taken from the A register.  As with LDA, the
+    /// memory word is also copied into the E register.
+    fn fetch_arithmetic_operand(
+        &mut self,
+        ctx: &Context,
+        mem: &mut MemoryUnit,
+    ) -> Result<Unsigned36Bit, Alarm> {
+        let target: Address = self.operand_address_with_optional_defer_and_index(ctx, mem)?;
+        let a = mem.get_a_register();
+        let (operand, _extra) =
+            self.fetch_operand_from_address_with_exchange(ctx, mem, &target, &a, &UpdateE::Yes)?;
+        Ok(operand)
+    }
+
+    fn add_to_a_register(
+        &mut self,
+        mem: &mut MemoryUnit,
+        addend: Unsigned36Bit,
+    ) -> Result<(), Alarm> {
+        let a = mem.get_a_register();
+        let (result, overflow) = add_by_subwords(&self.get_config(), a, addend);
+        event!(
+            Level::TRACE,
+            "{:o} + {:o} = {:o} (overflow {:?})",
+            a,
+            addend,
+            result,
+            overflow
+        );
+        mem.set_a_register(result);
+        self.record_overflow(overflow)
+    }
+
+    /// Set the overflow indications of the subwords which overflowed
+    /// during the current instruction.  If any did, raise OSAL
+    /// (which, if it is masked, does not stop execution).  The result
+    /// of the instruction should already have been stored, since on
+    /// the TX-2 the overflow is detected as the result is formed.
+    pub(crate) fn record_overflow(&mut self, overflow: OverflowLatch) -> Result<(), Alarm> {
+        self.regs.overflow.set(overflow);
+        if overflow.is_set() {
+            self.fire_details_if_not_masked(AlarmDetails::OSAL(
+                self.regs.n,
+                format!(
+                    "overflow in quarters {:04b} (quarter 1 is rightmost)",
+                    overflow.0
+                ),
+            ))
+        } else {
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::alarm::AlarmKind;
+    use super::super::super::context::Context;
+    use super::super::super::control::ConfigurationMemorySetup;
+    use super::super::super::exchanger::SystemConfiguration;
+    use super::super::super::memory::MetaBitChange;
+    use super::super::super::{MemoryConfiguration, MemoryUnit};
+    use super::super::tests::{execute_single_instruction, make_ctx};
+    use super::super::{ControlUnit, PanicOnUnmaskedAlarm, UpdateE};
+    use super::OverflowLatch;
+    use base::prelude::*;
+
+    const COMPLAIN: &str = "failed to set up arithmetic test data";
+    const OPERAND_ADDRESS: Address = Address::new(u18!(0o100));
+
+    fn setup(
+        ctx: &Context,
+        a: Unsigned36Bit,
+        b: Unsigned36Bit,
+        operand: Unsigned36Bit,
+    ) -> (ControlUnit, MemoryUnit) {
+        let mut control = ControlUnit::new(
+            PanicOnUnmaskedAlarm::Yes,
+            ConfigurationMemorySetup::StandardForTestingOnly,
+        );
+        let mut mem = MemoryUnit::new(
+            ctx,
+            &MemoryConfiguration {
+                with_u_memory: false,
+            },
+        );
+        control
+            .memory_store_without_exchange(
+                ctx,
+                &mut mem,
+                &OPERAND_ADDRESS,
+                &operand,
+                &UpdateE::No,
+                &MetaBitChange::None,
+            )
+            .expect(COMPLAIN);
+        mem.set_a_register(a);
+        mem.set_b_register(b);
+        (control, mem)
+    }
+
+    /// Execute an arithmetic instruction with configuration
+    /// `cfg_index` (which selects a location in the standard F-memory
+    /// setup), returning the resulting A register and overflow latch.
+    fn simulate(
+        opcode: Opcode,
+        cfg_index: u8,
+        a: Unsigned36Bit,
+        operand: Unsigned36Bit,
+    ) -> (Unsigned36Bit, OverflowLatch) {
+        let (a, _, overflow) = simulate_ab(opcode, cfg_index, a, Unsigned36Bit::ZERO, operand);
+        (a, overflow)
+    }
+
+    /// Execute an arithmetic instruction as for [`simulate`] but
+    /// also specifying the initial value of B and returning the
+    /// final value of B.
+    fn simulate_ab(
+        opcode: Opcode,
+        cfg_index: u8,
+        a: Unsigned36Bit,
+        b: Unsigned36Bit,
+        operand: Unsigned36Bit,
+    ) -> (Unsigned36Bit, Unsigned36Bit, OverflowLatch) {
+        let ctx = make_ctx();
+        let (mut control, mut mem) = setup(&ctx, a, b, operand);
+        let inst = SymbolicInstruction {
+            held: false,
+            configuration: Unsigned5Bit::try_from(cfg_index).expect(COMPLAIN),
+            opcode,
+            index: Unsigned6Bit::ZERO,
+            operand_address: OperandAddress::Direct(OPERAND_ADDRESS),
+        };
+        let result = execute_single_instruction(&ctx, &mut control, &mut mem, &inst);
+        if let Err(e) = result {
+            panic!("{:?} instruction failed: {}", opcode, e);
+        }
+        assert_eq!(
+            mem.get_e_register(),
+            operand,
+            "E should contain the operand"
+        );
+        (
+            mem.get_a_register(),
+            mem.get_b_register(),
+            control.regs.overflow,
+        )
+    }
+
+    fn no_overflow() -> OverflowLatch {
+        OverflowLatch(0)
+    }
+
+    /// Check that overflow raises OSAL, which is masked by default
+    /// (so that ADD completes normally) but stops execution when it
+    /// is unmasked.
+    #[test]
+    fn test_overflow_alarm() {
+        let ctx = make_ctx();
+        let inst = SymbolicInstruction {
+            held: false,
+            configuration: Unsigned5Bit::ZERO,
+            opcode: Opcode::Add,
+            index: Unsigned6Bit::ZERO,
+            operand_address: OperandAddress::Direct(OPERAND_ADDRESS),
+        };
+        for masked in [true, false] {
+            let (_, mut mem) = setup(&ctx, u36!(0o377_777_777_777), Unsigned36Bit::ZERO, u36!(1));
+            let mut control = ControlUnit::new(
+                PanicOnUnmaskedAlarm::No,
+                ConfigurationMemorySetup::StandardForTestingOnly,
+            );
+            if !masked {
+                control
+                    .set_alarm_masked(AlarmKind::OSAL, false)
+                    .expect(COMPLAIN);
+            }
+            let result = execute_single_instruction(&ctx, &mut control, &mut mem, &inst);
+            assert_eq!(result.is_ok(), masked);
+            assert_eq!(control.unmasked_alarm_active(), !masked);
+            let status = control
+                .get_status_of_alarm("OSAL")
+                .expect("OSAL should be a known alarm");
+            assert!(status.active);
+            assert_eq!(status.masked, masked);
+            // The result is stored even when the alarm fires.
+            assert_eq!(mem.get_a_register(), u36!(0o400_000_000_000));
+            assert!(control.regs.overflow.is_set());
+        }
+    }
+
+    #[test]
+    fn test_add_full_word() {
+        // F-memory location 0 (configuration 000) is the full word.
+        assert_eq!(
+            simulate(Opcode::Add, 0, u36!(1), u36!(2)),
+            (u36!(3), no_overflow())
+        );
+        // -1 + 1 gives minus zero.
+        assert_eq!(
+            simulate(Opcode::Add, 0, u36!(0o777_777_777_776), u36!(1)),
+            (u36!(0o777_777_777_777), no_overflow())
+        );
+        // -1 + -1 = -2 (this requires an end-around carry).
+        assert_eq!(
+            simulate(
+                Opcode::Add,
+                0,
+                u36!(0o777_777_777_776),
+                u36!(0o777_777_777_776)
+            ),
+            (u36!(0o777_777_777_775), no_overflow())
+        );
+        // The largest positive value, plus one, overflows.
+        assert_eq!(
+            simulate(Opcode::Add, 0, u36!(0o377_777_777_777), u36!(1)),
+            (u36!(0o400_000_000_000), OverflowLatch(0b1111))
+        );
+        // The most negative value, plus -1, overflows.
+        assert_eq!(
+            simulate(
+                Opcode::Add,
+                0,
+                u36!(0o400_000_000_000),
+                u36!(0o777_777_777_776)
+            ),
+            (u36!(0o377_777_777_777), OverflowLatch(0b1111))
+        );
+    }
+
+    #[test]
+    fn test_sub_full_word() {
+        assert_eq!(
+            simulate(Opcode::Sub, 0, u36!(5), u36!(3)),
+            (u36!(2), no_overflow())
+        );
+        assert_eq!(
+            simulate(Opcode::Sub, 0, u36!(3), u36!(5)),
+            (u36!(0o777_777_777_775), no_overflow())
+        );
+        // The most negative value, minus one, overflows.
+        assert_eq!(
+            simulate(Opcode::Sub, 0, u36!(0o400_000_000_000), u36!(1)),
+            (u36!(0o377_777_777_777), OverflowLatch(0b1111))
+        );
+    }
+
+    #[test]
+    fn test_add_halves() {
+        // F-memory location 16 is configuration 200 (both halves,
+        // no permutation).  The halves are added separately, and only
+        // the right half overflows.
+        assert_eq!(
+            simulate(
+                Opcode::Add,
+                16,
+                u36!(0o000_001_377_777),
+                u36!(0o000_002_000_001)
+            ),
+            (u36!(0o000_003_400_000), OverflowLatch(0b0011))
+        );
+        // The end-around carry does not cross from one half to the
+        // other.
+        assert_eq!(
+            simulate(
+                Opcode::Add,
+                16,
+                u36!(0o000_001_777_776),
+                u36!(0o000_001_000_002)
+            ),
+            (u36!(0o000_002_000_001), no_overflow())
+        );
+    }
+
+    #[test]
+    fn test_add_right_half_only() {
+        // F-memory location 1 is configuration 340 (right half only).
+        // The left half of A is unchanged.
+        assert_eq!(
+            simulate(
+                Opcode::Add,
+                1,
+                u36!(0o123_456_000_005),
+                u36!(0o777_777_000_003)
+            ),
+            (u36!(0o123_456_000_010), no_overflow())
+        );
+    }
+
+    #[test]
+    fn test_sub_left_half_only() {
+        // F-memory location 29 is configuration 330 (left half only).
+        // The right half of A is unchanged.
+        assert_eq!(
+            simulate(
+                Opcode::Sub,
+                29,
+                u36!(0o000_010_654_321),
+                u36!(0o000_003_777_777)
+            ),
+            (u36!(0o000_005_654_321), no_overflow())
+        );
+    }
+
+    #[test]
+    fn test_add_quarters() {
+        // F-memory location 24 is configuration 600 (four quarters,
+        // no permutation).
+        assert_eq!(
+            simulate(
+                Opcode::Add,
+                24,
+                u36!(0o001_377_003_777),
+                u36!(0o001_001_001_001)
+            ),
+            (u36!(0o002_400_004_001), OverflowLatch(0b0100))
+        );
+    }
+
+    #[test]
+    fn test_add_three_one() {
+        // F-memory location 7 is configuration 410 (the 27-bit
+        // subword only).  Quarter 1 of A is unchanged.
+        assert_eq!(
+            simulate(
+                Opcode::Add,
+                7,
+                u36!(0o000_000_001_123),
+                u36!(0o000_000_002_456)
+            ),
+            (u36!(0o000_000_003_123), no_overflow())
+        );
+    }
+
+    #[test]
+    fn test_add_partially_active_full_word() {
+        // F-memory location 11 is configuration 160 (full word, but
+        // only quarter 1 is active).  The operand is sign-extended
+        // from quarter 1 and so the addition here is 5 + -4.
+        assert_eq!(
+            simulate(
+                Opcode::Add,
+                11,
+                u36!(0o000_000_000_005),
+                u36!(0o000_000_000_773)
+            ),
+            (u36!(0o000_000_000_001), no_overflow())
+        );
+    }
+
+    /// For every configuration in the standard F-memory setup, check
+    /// that ADD and SUB do not alter the inactive quarters of A, and
+    /// that SUB undoes the effect of ADD (where no overflow occurs).
+    #[test]
+    fn test_add_sub_all_standard_configurations() {
+        let a = u36!(0o012_034_056_070);
+        let operand = u36!(0o001_002_003_004);
+        for cfg_index in 0..32_u8 {
+            let cfg: SystemConfiguration =
+                super::super::super::exchanger::standard_plugboard_f_memory_settings()
+                    [usize::from(cfg_index)];
+            let inactive_mask: u64 = (0..4_u8)
+                .filter(|q| !cfg.active_quarters().is_active(q))
+                .fold(0, |acc, q| acc | (0o777 << (u32::from(q) * 9)));
+
+            let (sum, overflow) = simulate(Opcode::Add, cfg_index, a, operand);
+            assert_eq!(
+                overflow,
+                no_overflow(),
+                "ADD should not overflow for configuration {cfg:o}"
+            );
+            assert_eq!(
+                u64::from(sum) & inactive_mask,
+                u64::from(a) & inactive_mask,
+                "ADD should not change inactive quarters for configuration {cfg:o}"
+            );
+
+            let (difference, overflow) = simulate(Opcode::Sub, cfg_index, sum, operand);
+            assert_eq!(
+                overflow,
+                no_overflow(),
+                "SUB should not overflow for configuration {cfg:o}"
+            );
+            assert_eq!(
+                difference, a,
+                "SUB should undo ADD for configuration {cfg:o}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_mul_full_word() {
+        // The product of small integers appears in B.
+        assert_eq!(
+            simulate_ab(Opcode::Mul, 0, u36!(3), u36!(0o777), u36!(5)),
+            (u36!(0), u36!(15), no_overflow())
+        );
+        // -3 * 5 = -15; both A and B are given the sign of the product.
+        assert_eq!(
+            simulate_ab(
+                Opcode::Mul,
+                0,
+                u36!(0o777_777_777_774),
+                Unsigned36Bit::ZERO,
+                u36!(5)
+            ),
+            (
+                u36!(0o777_777_777_777),
+                u36!(0o777_777_777_760),
+                no_overflow()
+            )
+        );
+        // -3 * -5 = 15.
+        assert_eq!(
+            simulate_ab(
+                Opcode::Mul,
+                0,
+                u36!(0o777_777_777_774),
+                Unsigned36Bit::ZERO,
+                u36!(0o777_777_777_772)
+            ),
+            (u36!(0), u36!(15), no_overflow())
+        );
+        // (2^35 - 1) * 2 = 2^36 - 2, and this does not fit in B.
+        assert_eq!(
+            simulate_ab(
+                Opcode::Mul,
+                0,
+                u36!(0o377_777_777_777),
+                Unsigned36Bit::ZERO,
+                u36!(2)
+            ),
+            (u36!(1), u36!(0o377_777_777_776), no_overflow())
+        );
+    }
+
+    #[test]
+    fn test_mul_halves() {
+        // F-memory location 16 is configuration 200 (both halves).
+        // On the left, 3 * 5 = 15; on the right 2 * -2 = -4.
+        assert_eq!(
+            simulate_ab(
+                Opcode::Mul,
+                16,
+                u36!(0o000_003_000_002),
+                Unsigned36Bit::ZERO,
+                u36!(0o000_005_777_775)
+            ),
+            (
+                u36!(0o000_000_777_777),
+                u36!(0o000_017_777_773),
+                no_overflow()
+            )
+        );
+    }
+
+    #[test]
+    fn test_mul_quarters() {
+        // F-memory location 24 is configuration 600 (four quarters).
+        assert_eq!(
+            simulate_ab(
+                Opcode::Mul,
+                24,
+                u36!(0o002_003_004_005),
+                Unsigned36Bit::ZERO,
+                u36!(0o003_003_003_003)
+            ),
+            (u36!(0), u36!(0o006_011_014_017), no_overflow())
+        );
+    }
+
+    #[test]
+    fn test_mul_right_half_only() {
+        // F-memory location 1 is configuration 340 (right half only).
+        // The left halves of A and B are unchanged.
+        assert_eq!(
+            simulate_ab(
+                Opcode::Mul,
+                1,
+                u36!(0o123_456_000_007),
+                u36!(0o654_321_000_000),
+                u36!(0o000_000_000_006)
+            ),
+            (
+                u36!(0o123_456_000_000),
+                u36!(0o654_321_000_052),
+                no_overflow()
+            )
+        );
+    }
+
+    #[test]
+    fn test_div_full_word() {
+        assert_eq!(
+            simulate_ab(Opcode::Div, 0, u36!(0), u36!(15), u36!(5)),
+            (u36!(3), u36!(0), no_overflow())
+        );
+        assert_eq!(
+            simulate_ab(Opcode::Div, 0, u36!(0), u36!(17), u36!(5)),
+            (u36!(3), u36!(2), no_overflow())
+        );
+        // -17 / 5 gives a quotient of -3 and a remainder of -2.
+        assert_eq!(
+            simulate_ab(
+                Opcode::Div,
+                0,
+                u36!(0o777_777_777_777),
+                u36!(0o777_777_777_756),
+                u36!(5)
+            ),
+            (
+                u36!(0o777_777_777_774),
+                u36!(0o777_777_777_775),
+                no_overflow()
+            )
+        );
+        // -17 / -5 gives a quotient of 3 and a remainder of -2.
+        assert_eq!(
+            simulate_ab(
+                Opcode::Div,
+                0,
+                u36!(0o777_777_777_777),
+                u36!(0o777_777_777_756),
+                u36!(0o777_777_777_772)
+            ),
+            (u36!(3), u36!(0o777_777_777_775), no_overflow())
+        );
+    }
+
+    #[test]
+    fn test_div_inverts_mul() {
+        let (a, b, _) = simulate_ab(
+            Opcode::Mul,
+            0,
+            u36!(0o123_456_701_234),
+            Unsigned36Bit::ZERO,
+            u36!(0o765_432_107_654),
+        );
+        // The product is negative, so the (zero) remainder is minus
+        // zero.
+        assert_eq!(
+            simulate_ab(Opcode::Div, 0, a, b, u36!(0o765_432_107_654)),
+            (
+                u36!(0o123_456_701_234),
+                u36!(0o777_777_777_777),
+                no_overflow()
+            )
+        );
+    }
+
+    #[test]
+    fn test_div_overflow() {
+        // The quotient would not fit in A.  A and B are unchanged.
+        assert_eq!(
+            simulate_ab(Opcode::Div, 0, u36!(5), u36!(0), u36!(5)),
+            (u36!(5), u36!(0), OverflowLatch(0b1111))
+        );
+        // Division by zero.
+        assert_eq!(
+            simulate_ab(Opcode::Div, 0, u36!(0), u36!(7), u36!(0)),
+            (u36!(0), u36!(7), OverflowLatch(0b1111))
+        );
+        // Division by minus zero.
+        assert_eq!(
+            simulate_ab(Opcode::Div, 0, u36!(0), u36!(7), u36!(0o777_777_777_777)),
+            (u36!(0), u36!(7), OverflowLatch(0b1111))
+        );
+    }
+
+    #[test]
+    fn test_div_halves() {
+        // F-memory location 16 is configuration 200 (both halves).
+        // The left half divides 15 by 0, which overflows; the right
+        // half divides 9 by 2.
+        assert_eq!(
+            simulate_ab(
+                Opcode::Div,
+                16,
+                u36!(0o000_000_000_000),
+                u36!(0o000_017_000_011),
+                u36!(0o000_000_000_002)
+            ),
+            (
+                u36!(0o000_000_000_004),
+                u36!(0o000_017_000_001),
+                OverflowLatch(0b1100)
+            )
+        );
+    }
+
+    /// For every configuration in the standard F-memory setup, check
+    /// that MUL and DIV do not alter the inactive quarters of A or B.
+    #[test]
+    fn test_mul_div_all_standard_configurations() {
+        let a = u36!(0o012_034_056_070);
+        let b = u36!(0o021_043_065_007);
+        let operand = u36!(0o001_002_003_004);
+        for cfg_index in 0..32_u8 {
+            let cfg: SystemConfiguration =
+                super::super::super::exchanger::standard_plugboard_f_memory_settings()
+                    [usize::from(cfg_index)];
+            let inactive_mask: u64 = (0..4_u8)
+                .filter(|q| !cfg.active_quarters().is_active(q))
+                .fold(0, |acc, q| acc | (0o777 << (u32::from(q) * 9)));
+            for opcode in [Opcode::Mul, Opcode::Div] {
+                let (new_a, new_b, _) = simulate_ab(opcode, cfg_index, a, b, operand);
+                assert_eq!(
+                    u64::from(new_a) & inactive_mask,
+                    u64::from(a) & inactive_mask,
+                    "{opcode:?} should not change inactive quarters of A for configuration {cfg:o}"
+                );
+                assert_eq!(
+                    u64::from(new_b) & inactive_mask,
+                    u64::from(b) & inactive_mask,
+                    "{opcode:?} should not change inactive quarters of B for configuration {cfg:o}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_mul_div_work() {
+        use super::super::timing::ArithmeticWork;
+        use super::{divide_work, multiply_work};
+        let settings = super::super::super::exchanger::standard_plugboard_f_memory_settings();
+        // F-memory location 0 is the full word, location 16 is
+        // both halves.
+        let (full_word, halves) = (&settings[0], &settings[16]);
+        assert_eq!(
+            multiply_work(full_word, u36!(0o000_000_000_013)),
+            ArithmeticWork {
+                steps: 35,
+                additions: 3
+            }
+        );
+        // The sign of the multiplier does not count as an addition.
+        assert_eq!(
+            multiply_work(full_word, u36!(0o777_777_777_764)),
+            ArithmeticWork {
+                steps: 35,
+                additions: 3
+            }
+        );
+        // The halves are multiplied in parallel, so the work is that
+        // of the half needing the most additions.
+        assert_eq!(
+            multiply_work(halves, u36!(0o000_001_000_007)),
+            ArithmeticWork {
+                steps: 17,
+                additions: 3
+            }
+        );
+        assert_eq!(
+            divide_work(halves, &OverflowLatch(0)),
+            ArithmeticWork {
+                steps: 17,
+                additions: 0
+            }
+        );
+        // Subwords which overflow are not divided.
+        assert_eq!(
+            divide_work(full_word, &OverflowLatch(0b1111)),
+            ArithmeticWork::default()
+        );
+    }
+
+    #[test]
+    fn test_tly_work() {
+        use super::super::timing::ArithmeticWork;
+        use super::tally_work;
+        let settings = super::super::super::exchanger::standard_plugboard_f_memory_settings();
+        // F-memory location 0 is the full word, location 1 is the
+        // right half only and location 16 is both halves.
+        for (cfg_index, steps) in [(0, 36), (1, 18), (16, 18)] {
+            assert_eq!(
+                tally_work(&settings[cfg_index]),
+                ArithmeticWork {
+                    steps,
+                    additions: 0
+                },
+                "configuration index {cfg_index}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_tly_full_word() {
+        // F-memory location 0 (configuration 000) is the full word.
+        assert_eq!(
+            simulate(Opcode::Tly, 0, u36!(0o123), u36!(0o777_000_000_001)),
+            (u36!(10), no_overflow())
+        );
+        assert_eq!(
+            simulate(Opcode::Tly, 0, u36!(0o123), u36!(0)),
+            (u36!(0), no_overflow())
+        );
+        assert_eq!(
+            simulate(Opcode::Tly, 0, u36!(0o123), u36!(0o777_777_777_777)),
+            (u36!(36), no_overflow())
+        );
+    }
+
+    #[test]
+    fn test_tly_halves() {
+        // F-memory location 16 is configuration 200 (both halves).
+        assert_eq!(
+            simulate(
+                Opcode::Tly,
+                16,
+                u36!(0o123_456_654_321),
+                u36!(0o000_007_400_000)
+            ),
+            (u36!(0o000_003_000_001), no_overflow())
+        );
+    }
+
+    #[test]
+    fn test_tly_right_half_only() {
+        // F-memory location 1 is configuration 340 (right half only).
+        // The left half of A is unchanged and the left half of the
+        // operand is not counted.
+        assert_eq!(
+            simulate(
+                Opcode::Tly,
+                1,
+                u36!(0o123_456_654_321),
+                u36!(0o777_777_000_017)
+            ),
+            (u36!(0o123_456_000_004), no_overflow())
+        );
+    }
+
+    #[test]
+    fn test_tly_left_half() {
+        // F-memory location 29 is configuration 330 (the left half,
+        // in which only quarter 3 is active).
+        assert_eq!(
+            simulate(
+                Opcode::Tly,
+                29,
+                u36!(0o123_456_654_321),
+                u36!(0o777_003_777_777)
+            ),
+            (u36!(0o123_002_654_321), no_overflow())
+        );
+    }
+
+    #[test]
+    fn test_tly_quarters() {
+        // F-memory location 24 is configuration 600 (four quarters).
+        assert_eq!(
+            simulate(
+                Opcode::Tly,
+                24,
+                u36!(0o123_456_654_321),
+                u36!(0o777_001_003_000)
+            ),
+            (u36!(0o011_001_002_000), no_overflow())
+        );
+    }
+
+    #[test]
+    fn test_tly_three_one() {
+        // F-memory location 7 is configuration 410 (the 27-bit
+        // subword only).  Quarter 1 of A is unchanged.
+        assert_eq!(
+            simulate(
+                Opcode::Tly,
+                7,
+                u36!(0o123_456_654_321),
+                u36!(0o700_000_001_777)
+            ),
+            (u36!(0o000_000_004_321), no_overflow())
+        );
+    }
+
+    #[test]
+    fn test_tly_partially_active_full_word() {
+        // F-memory location 11 is configuration 160 (full word, but
+        // only quarter 1 is active).  The operand is sign-extended
+        // from quarter 1 but the bits supplied by sign extension are
+        // not counted.
+        assert_eq!(
+            simulate(
+                Opcode::Tly,
+                11,
+                u36!(0o123_456_654_321),
+                u36!(0o000_000_000_773)
+            ),
+            (u36!(0o123_456_654_010), no_overflow())
+        );
+    }
+}
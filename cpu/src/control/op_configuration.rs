@@ -1,9 +1,12 @@
 //! Implementations of "Configuration Memory Class" opcodes
 //!
-//! - SPF (unimplemented)
+//! - SPF: [`ControlUnit::op_spf`]
 //! - SPG: [`ControlUnit::op_spg`]
-//! - FLF (unimplemented)
-//! - FLG (unimplemented)
+//! - FLF: [`ControlUnit::op_flf`]
+//! - FLG: [`ControlUnit::op_flg`]
+//!
+//! F-memory location 0 always contains the configuration 000.
+//! Attempts to change it are ignored.
 
 use base::prelude::*;
 use base::subword;
@@ -18,12 +21,30 @@ use tracing::{event, Level};
 
 /// ## "Configuration Memory Class" opcodes
 ///
-/// - SPF (unimplemented)
+/// - SPF: [`ControlUnit::op_spf`]
 /// - SPG: [`ControlUnit::op_spg`]
-/// - FLF (unimplemented)
-/// - FLG (unimplemented)
+/// - FLF: [`ControlUnit::op_flf`]
+/// - FLG: [`ControlUnit::op_flg`]
 ///
 impl ControlUnit {
+    /// Implements the SPF instruction (Opcode 021).  F-memory
+    /// location c (where c is the configuration syllable of the
+    /// instruction) is loaded from quarter 1 of the operand.  As
+    /// for SPG, the operand is also loaded into E.
+    pub(crate) fn op_spf(
+        &mut self,
+        ctx: &Context,
+        mem: &mut MemoryUnit,
+    ) -> Result<OpcodeResult, Alarm> {
+        let c = usize::from(self.regs.n.configuration());
+        let target = self.operand_address_with_optional_defer_and_index(ctx, mem)?;
+        let (word, _meta) =
+            self.fetch_operand_from_address_without_exchange(ctx, mem, &target, &UpdateE::Yes)?;
+        let [_, _, _, q1] = subword::quarters(word);
+        self.set_f_memory(c, q1.into());
+        Ok(OpcodeResult::default())
+    }
+
     /// Implements the SPG instruction.
     pub(crate) fn op_spg(
         &mut self,
@@ -35,20 +56,106 @@ impl ControlUnit {
         let (word, _meta) =
             self.fetch_operand_from_address_without_exchange(ctx, mem, &target, &UpdateE::Yes)?;
         for (quarter_number, cfg_value) in subword::quarters(word).iter().rev().enumerate() {
-            let pos = c + quarter_number;
-            let newvalue = (*cfg_value).into();
-            if pos != 0 {
-                self.regs.f_memory[pos] = newvalue;
-            } else if newvalue != SystemConfiguration::zero() {
-                event!(
-                    Level::WARN,
-                    "Ignoring attempt to set system configuration 0 to {:?}",
-                    newvalue
-                );
-            }
+            self.set_f_memory(c + quarter_number, (*cfg_value).into());
         }
         Ok(OpcodeResult::default())
     }
+
+    /// Implements the FLF instruction (Opcode 031).  This is the
+    /// inverse of SPF; the contents of F-memory location c are
+    /// stored in quarter 1 of the operand, and the other quarters
+    /// of the operand are cleared.  The stored value is also loaded
+    /// into E.
+    pub(crate) fn op_flf(
+        &mut self,
+        ctx: &Context,
+        mem: &mut MemoryUnit,
+    ) -> Result<OpcodeResult, Alarm> {
+        let c = self.regs.n.configuration();
+        let target = self.operand_address_with_optional_defer_and_index(ctx, mem)?;
+        let q1: Unsigned9Bit = self.regs.get_f_mem(c).into();
+        let word = subword::join_halves(
+            Unsigned18Bit::ZERO,
+            subword::join_quarters(Unsigned9Bit::ZERO, q1),
+        );
+        self.store_f_memory_word(ctx, mem, &target, word)
+    }
+
+    /// Implements the FLG instruction (Opcode 032).  This is the
+    /// inverse of SPG; the contents of F-memory locations c, c+1,
+    /// c+2 and c+3 are stored in quarters 1, 2, 3 and 4 of the
+    /// operand.  The stored value is also loaded into E.
+    pub(crate) fn op_flg(
+        &mut self,
+        ctx: &Context,
+        mem: &mut MemoryUnit,
+    ) -> Result<OpcodeResult, Alarm> {
+        let c = u8::from(self.regs.n.configuration());
+        let target = self.operand_address_with_optional_defer_and_index(ctx, mem)?;
+        let quarter = |offset: u8| -> Unsigned9Bit {
+            // We assume that the F-memory address does not wrap
+            // around, so locations beyond 037 read as zero.
+            match Unsigned5Bit::try_from(c + offset) {
+                Ok(pos) => self.regs.get_f_mem(pos).into(),
+                Err(_) => Unsigned9Bit::ZERO,
+            }
+        };
+        let word = subword::join_halves(
+            subword::join_quarters(quarter(3), quarter(2)),
+            subword::join_quarters(quarter(1), quarter(0)),
+        );
+        self.store_f_memory_word(ctx, mem, &target, word)
+    }
+
+    fn store_f_memory_word(
+        &mut self,
+        ctx: &Context,
+        mem: &mut MemoryUnit,
+        target: &Address,
+        word: Unsigned36Bit,
+    ) -> Result<OpcodeResult, Alarm> {
+        event!(
+            Level::TRACE,
+            "storing F-memory contents {word:o} at {target:o}"
+        );
+        self.memory_store_without_exchange(
+            ctx,
+            mem,
+            target,
+            &word,
+            &UpdateE::Yes,
+            &self.write_operand_metaop(),
+        )
+        .map(|()| OpcodeResult::default())
+    }
+
+    /// Set F-memory location `pos` to `newvalue`.  Location 0 always
+    /// contains 000, and so changes to it are ignored.  As for FLG,
+    /// we assume that the F-memory address does not wrap around, so
+    /// SPG ignores quarters which would go beyond location 037.
+    fn set_f_memory(&mut self, pos: usize, newvalue: SystemConfiguration) {
+        if pos != 0 {
+            match self.regs.f_memory.get_mut(pos) {
+                Some(loc) => {
+                    *loc = newvalue;
+                }
+                None => {
+                    event!(
+                        Level::DEBUG,
+                        "Ignoring attempt to set system configuration {:o} (beyond 037) to {:?}",
+                        pos,
+                        newvalue
+                    );
+                }
+            }
+        } else if newvalue != SystemConfiguration::zero() {
+            event!(
+                Level::WARN,
+                "Ignoring attempt to set system configuration 0 to {:?}",
+                newvalue
+            );
+        }
+    }
 }
 
 #[cfg(test)]
@@ -56,8 +163,9 @@ mod tests {
     use super::super::super::context::Context;
     use super::super::super::control::{ConfigurationMemorySetup, PanicOnUnmaskedAlarm, UpdateE};
     use super::super::super::exchanger::SystemConfiguration;
-    use super::super::super::memory::MetaBitChange;
+    use super::super::super::memory::{MemoryMapped, MetaBitChange};
     use super::super::super::{MemoryConfiguration, MemoryUnit};
+    use super::super::tests::execute_single_instruction;
     use base::instruction::{Opcode, SymbolicInstruction};
     use base::prelude::*;
     use core::time::Duration;
@@ -188,4 +296,190 @@ mod tests {
         // SPG should set the E register.
         assert_eq!(e, word, "E register was unaffected");
     }
+
+    /// Execute the configuration instruction `opcode` with
+    /// configuration syllable `cfg` and operand address 0o100
+    /// (which initially contains `word`).  Returns the final
+    /// contents of 0o100 and of E.
+    fn simulate_config_op(
+        ctx: &Context,
+        control: &mut ControlUnit,
+        mem: &mut MemoryUnit,
+        opcode: Opcode,
+        cfg: u8,
+        word: Unsigned36Bit,
+    ) -> (Unsigned36Bit, Unsigned36Bit) {
+        const COMPLAIN: &str = "failed to set up test data";
+        let address = Address::from(u18!(0o100));
+        control
+            .memory_store_without_exchange(
+                ctx,
+                mem,
+                &address,
+                &word,
+                &UpdateE::No,
+                &MetaBitChange::None,
+            )
+            .expect(COMPLAIN);
+        let inst = SymbolicInstruction {
+            held: false,
+            configuration: Unsigned5Bit::try_from(cfg).expect(COMPLAIN),
+            opcode,
+            index: Unsigned6Bit::ZERO,
+            operand_address: OperandAddress::Direct(address),
+        };
+        let result = execute_single_instruction(ctx, control, mem, &inst);
+        if let Err(e) = result {
+            panic!("{:?} instruction failed: {}", opcode, e);
+        }
+        match mem.fetch(ctx, &address, &MetaBitChange::None) {
+            Ok((stored, _)) => (stored, mem.get_e_register()),
+            Err(e) => {
+                panic!("unable to retrieve the operand: {}", e);
+            }
+        }
+    }
+
+    fn new_control_and_memory(ctx: &Context) -> (ControlUnit, MemoryUnit) {
+        (
+            ControlUnit::new(
+                PanicOnUnmaskedAlarm::Yes,
+                ConfigurationMemorySetup::StandardForTestingOnly,
+            ),
+            MemoryUnit::new(
+                ctx,
+                &MemoryConfiguration {
+                    with_u_memory: false,
+                },
+            ),
+        )
+    }
+
+    #[test]
+    fn op_spf_sets_one_location() {
+        let ctx = make_ctx();
+        let (mut control, mut mem) = new_control_and_memory(&ctx);
+        let before = control.regs.f_memory;
+        let word = u36!(0o111_222_333_444);
+        let (_, e) = simulate_config_op(&ctx, &mut control, &mut mem, Opcode::Spf, 5, word);
+        assert_eq!(control.regs.get_f_mem(cfg_loc(5)), cfg_val(0o444));
+        for (pos, (old, new)) in before.iter().zip(control.regs.f_memory.iter()).enumerate() {
+            if pos != 5 {
+                assert_eq!(old, new, "F-memory location {pos} should be unchanged");
+            }
+        }
+        assert_eq!(e, word, "SPF should set the E register");
+    }
+
+    #[test]
+    fn op_spf_zero_invariant() {
+        let ctx = make_ctx();
+        let (mut control, mut mem) = new_control_and_memory(&ctx);
+        simulate_config_op(
+            &ctx,
+            &mut control,
+            &mut mem,
+            Opcode::Spf,
+            0,
+            u36!(0o777_777_777_777),
+        );
+        assert_eq!(control.regs.get_f_mem(cfg_loc(0)), cfg_val(0));
+    }
+
+    #[test]
+    fn op_flf() {
+        let ctx = make_ctx();
+        let (mut control, mut mem) = new_control_and_memory(&ctx);
+        // F-memory location 2 is standard configuration 342.
+        let (stored, e) = simulate_config_op(
+            &ctx,
+            &mut control,
+            &mut mem,
+            Opcode::Flf,
+            2,
+            u36!(0o777_777_777_777),
+        );
+        assert_eq!(stored, u36!(0o342));
+        assert_eq!(e, stored, "FLF should set the E register");
+        // F-memory location 0 is always zero.
+        let (stored, _) = simulate_config_op(
+            &ctx,
+            &mut control,
+            &mut mem,
+            Opcode::Flf,
+            0,
+            u36!(0o777_777_777_777),
+        );
+        assert_eq!(stored, Unsigned36Bit::ZERO);
+    }
+
+    #[test]
+    fn op_flg() {
+        let ctx = make_ctx();
+        let (mut control, mut mem) = new_control_and_memory(&ctx);
+        // F-memory locations 0 to 3 contain the standard
+        // configurations 000, 340, 342 and 760.
+        let (stored, e) =
+            simulate_config_op(&ctx, &mut control, &mut mem, Opcode::Flg, 0, u36!(0o123));
+        assert_eq!(stored, u36!(0o760_342_340_000));
+        assert_eq!(e, stored, "FLG should set the E register");
+        // Locations beyond 037 read as zero.
+        let (stored, _) =
+            simulate_config_op(&ctx, &mut control, &mut mem, Opcode::Flg, 0o36, u36!(0o123));
+        assert_eq!(stored, u36!(0o000_000_604_331));
+    }
+
+    /// Verify that FLG and SPG can be used to save and restore the
+    /// contents of F-memory.
+    #[test]
+    fn op_flg_spg_save_and_restore() {
+        let ctx = make_ctx();
+        let (mut control, mut mem) = new_control_and_memory(&ctx);
+        let original = control.regs.f_memory;
+        let saved: Vec<Unsigned36Bit> = (0..8_u8)
+            .map(|n| {
+                simulate_config_op(&ctx, &mut control, &mut mem, Opcode::Flg, n * 4, u36!(0)).0
+            })
+            .collect();
+        // Overwrite (almost) all of F-memory.
+        for n in 0..8_u8 {
+            simulate_config_op(
+                &ctx,
+                &mut control,
+                &mut mem,
+                Opcode::Spg,
+                n * 4,
+                u36!(0o777_777_777_777),
+            );
+        }
+        assert_ne!(control.regs.f_memory, original);
+        for (n, word) in (0..8_u8).zip(saved.iter()) {
+            simulate_config_op(&ctx, &mut control, &mut mem, Opcode::Spg, n * 4, *word);
+        }
+        assert_eq!(control.regs.f_memory, original);
+    }
+
+    /// SPG and FLG with a configuration syllable near the end of
+    /// F-memory must agree about locations beyond 037 (SPG ignores
+    /// them and FLG reads them as zero), and must not panic.
+    #[test]
+    fn op_flg_spg_round_trip_at_end_of_f_memory() {
+        let ctx = make_ctx();
+        let (mut control, mut mem) = new_control_and_memory(&ctx);
+        let before = control.regs.f_memory;
+        simulate_config_op(
+            &ctx,
+            &mut control,
+            &mut mem,
+            Opcode::Spg,
+            0o36,
+            u36!(0o444_333_222_111),
+        );
+        assert_eq!(control.regs.get_f_mem(cfg_loc(0o36)), cfg_val(0o111));
+        assert_eq!(control.regs.get_f_mem(cfg_loc(0o37)), cfg_val(0o222));
+        assert_eq!(before[..0o36], control.regs.f_memory[..0o36]);
+        let (stored, _) =
+            simulate_config_op(&ctx, &mut control, &mut mem, Opcode::Flg, 0o36, u36!(0o123));
+        assert_eq!(stored, u36!(0o000_000_222_111));
+    }
 }
@@ -1,9 +1,9 @@
 //! Implementations of "Index Register Class" opcodes
 //! - RSX [`ControlUnit::op_rsx`]
 //! - DPX: [`ControlUnit::op_dpx`]
-//! - EXX (unimplemented)
+//! - EXX: [`ControlUnit::op_exx`]
 //! - AUX [`ControlUnit::op_aux`]
-//! - ADX (unimplemented)
+//! - ADX: [`ControlUnit::op_adx`]
 //! - SKX: [`ControlUnit::op_skx`]
 //! - JPX: [`ControlUnit::op_jpx`]
 //! - JNX: [`ControlUnit::op_jnx`]
@@ -18,14 +18,25 @@ use super::context::Context;
 use super::control::{
     sign_extend_index_value, ControlUnit, OpcodeResult, ProgramCounterChange, UpdateE,
 };
+use super::exchanger::exchanged_value_for_load;
 use super::memory::{MemoryUnit, MetaBitChange};
+use super::op_arith::ones_complement_add;
+
+/// Index registers 40 to 77 hold the saved program counters
+/// ("placeholders") of sequences 40 to 77.  Bit 2.9 of a placeholder
+/// is not part of the address; it marks the sequence for the
+/// trap-on-change-sequence feature of unit 42.
+fn is_placeholder_register(j: Unsigned6Bit) -> bool {
+    u8::from(j) >= 0o40
+}
 
 /// ## "Index Register Class" opcodes
 ///
 /// - RSX: [`ControlUnit::op_rsx`]
 /// - DPX: [`ControlUnit::op_dpx`]
+/// - EXX: [`ControlUnit::op_exx`]
 /// - AUX [`ControlUnit::op_aux`]
-/// - ADX (unimplemented)
+/// - ADX: [`ControlUnit::op_adx`]
 /// - SKX: [`ControlUnit::op_skx`]
 /// - JPX: [`ControlUnit::op_jpx`]
 /// - JNX: [`ControlUnit::op_jnx`]
@@ -106,6 +117,55 @@ impl ControlUnit {
         Ok(OpcodeResult::default())
     }
 
+    /// Implements the ADX instruction (Opcode 015).  The right half
+    /// of the (exchanged) operand is added to Xj, with 18-bit one's
+    /// complement wraparound.  As for AUX, the index bits of the
+    /// instruction identify the register, so ADX is not indexed.
+    ///
+    /// The placeholders of sequences 40 to 77 are treated as 18-bit
+    /// values (as they are by AUX, RSX and EXX), so a carry can
+    /// change the mark bit (2.9).
+    pub(crate) fn op_adx(
+        &mut self,
+        ctx: &Context,
+        mem: &mut MemoryUnit,
+    ) -> Result<OpcodeResult, Alarm> {
+        let j = self.regs.n.index_address();
+        let source: Address = self.operand_address_with_optional_defer_without_index(ctx, mem)?;
+        // As for AUX, we perform the memory access even when j=0, so
+        // that any alarm is raised.
+        let (word, _extra) = self.fetch_operand_from_address_with_exchange(
+            ctx,
+            mem,
+            &source,
+            &Unsigned36Bit::ZERO,
+            &UpdateE::Yes,
+        )?;
+        if !j.is_zero() {
+            let m: Signed18Bit = subword::right_half(word).reinterpret_as_signed();
+            let xj: Signed18Bit = self.regs.get_index_register(j);
+            // Index register arithmetic does not detect overflow;
+            // the sum simply wraps around.
+            let (sum, _overflow) = ones_complement_add(
+                u64::from(xj.reinterpret_as_unsigned()),
+                u64::from(m.reinterpret_as_unsigned()),
+                18,
+            );
+            let newvalue: Signed18Bit = Unsigned18Bit::try_from(sum)
+                .expect("18-bit sum should fit in 18 bits")
+                .reinterpret_as_signed();
+            event!(
+                Level::TRACE,
+                "ADX: added {m:?} to {xj:?} yielding {newvalue:?} for X{j:o}",
+            );
+            if is_placeholder_register(j) {
+                self.warn_if_running_sequence_placeholder(j, "ADX");
+            }
+            self.regs.set_index_register(j, &newvalue);
+        }
+        Ok(OpcodeResult::default())
+    }
+
     /// Implements the RSX instruction (Opcode 011, User Handbook,
     /// page 3-14).
     pub(crate) fn op_rsx(
@@ -142,6 +202,68 @@ impl ControlUnit {
         Ok(OpcodeResult::default())
     }
 
+    /// Implements the EXX instruction (Opcode 014).  Xj is exchanged
+    /// with the right half of the (exchanged) operand: the old value
+    /// of Xj is stored in memory as DPX would store it, and Xj is
+    /// loaded from the old memory word as RSX would load it.  The
+    /// index bits of the instruction identify the register, so EXX
+    /// is not indexed.
+    ///
+    /// X₀ is always 0, so ⁰EXX stores zero and leaves X₀ unchanged.
+    /// For the placeholder registers (40 to 77) the full 18-bit value
+    /// is exchanged, including the mark bit.
+    pub(crate) fn op_exx(
+        &mut self,
+        ctx: &Context,
+        mem: &mut MemoryUnit,
+    ) -> Result<OpcodeResult, Alarm> {
+        let j = self.regs.n.index_address();
+        let xj: Signed18Bit = self.regs.get_index_register(j);
+        let target: Address = self.operand_address_with_optional_defer_without_index(ctx, mem)?;
+        let mut memword: Option<Unsigned36Bit> = None;
+        self.memory_read_and_update_with_exchange(ctx, mem, &target, &UpdateE::Yes, |existing| {
+            memword = Some(existing);
+            sign_extend_index_value(&xj)
+        })?;
+        match memword {
+            Some(memword) if !j.is_zero() => {
+                let existing = join_halves(Unsigned18Bit::ZERO, xj.reinterpret_as_unsigned());
+                let loaded = exchanged_value_for_load(&self.get_config(), &memword, &existing);
+                let new_xj: Signed18Bit = subword::right_half(loaded).reinterpret_as_signed();
+                event!(
+                    Level::TRACE,
+                    "EXX: X{j:o}={xj:?} stored at {target:o}, X{j:o} is now {new_xj:?}"
+                );
+                if is_placeholder_register(j) {
+                    self.warn_if_running_sequence_placeholder(j, "EXX");
+                }
+                self.regs.set_index_register(j, &new_xj);
+            }
+            Some(_) => {
+                // X₀ is always 0.
+            }
+            None => {
+                // The read failed but QSAL is masked, so the
+                // instruction has no effect.
+            }
+        }
+        Ok(OpcodeResult::default())
+    }
+
+    /// The placeholder of the currently running sequence is
+    /// overwritten with the value of P when the sequence next gives
+    /// up control, so an update to it made by the sequence itself
+    /// will be lost.  We faithfully update the register anyway, but
+    /// warn about it.
+    fn warn_if_running_sequence_placeholder(&self, j: Unsigned6Bit, name: &str) {
+        if self.regs.k == Some(j) {
+            event!(
+                Level::WARN,
+                "{name} modified X{j:o}, the placeholder of the currently running sequence; this change will be lost when the sequence is next dismissed or interrupted"
+            );
+        }
+    }
+
     /// Implements the SKX instruction (Opcode 012, User Handbook,
     /// page 3-24).
     pub(crate) fn op_skx(&mut self, _ctx: &Context) -> Result<OpcodeResult, Alarm> {
@@ -267,6 +389,7 @@ impl ControlUnit {
                 program_counter_change: Some(ProgramCounterChange::Jump(target)),
                 poll_order_change: None,
                 output: None,
+                arithmetic_work: None,
             })
         } else {
             Ok(OpcodeResult::default())
@@ -281,6 +404,7 @@ mod tests {
     use super::super::super::exchanger::SystemConfiguration;
     use super::super::super::memory::MetaBitChange;
     use super::super::super::{MemoryConfiguration, MemoryUnit};
+    use super::super::tests::execute_single_instruction;
     use base::instruction::{Opcode, SymbolicInstruction};
     use base::prelude::*;
     use core::time::Duration;
@@ -842,4 +966,269 @@ mod tests {
         assert_eq!(xj, 0); // X₀ cannot be changed.
         assert_eq!(e, w);
     }
+
+    /// Simulate an EXX or ADX instruction operating on the word at
+    /// 0o100, returning the final value of Xj and of the memory word.
+    fn simulate_exx_adx(
+        ctx: &Context,
+        opcode: Opcode,
+        j: Unsigned6Bit,
+        initial: Signed18Bit,
+        mem_word: Unsigned36Bit,
+        config_num: usize,
+    ) -> (Signed18Bit, Unsigned36Bit) {
+        const COMPLAIN: &str = "failed to set up EXX/ADX test data";
+        let address = Address::from(u18!(0o100));
+        let (mut control, mut mem) = setup(ctx, j, initial, &[(address, mem_word)], None);
+        let inst = SymbolicInstruction {
+            held: false,
+            configuration: Unsigned5Bit::try_from(config_num).expect(COMPLAIN),
+            opcode,
+            index: j,
+            operand_address: OperandAddress::Direct(address),
+        };
+        let result = execute_single_instruction(ctx, &mut control, &mut mem, &inst);
+        if let Err(e) = result {
+            panic!("{:?} instruction failed: {}", opcode, e);
+        }
+        let (word, _meta) = control
+            .fetch_operand_from_address_without_exchange(ctx, &mut mem, &address, &UpdateE::No)
+            .expect(COMPLAIN);
+        (control.regs.get_index_register(j), word)
+    }
+
+    #[test]
+    fn op_exx_full_word() {
+        let context = make_ctx();
+        // Standard configuration 0 is 000 (full word).  The index
+        // register is sign-extended into the left half, as for DPX.
+        assert_eq!(
+            simulate_exx_adx(
+                &context,
+                Opcode::Exx,
+                Unsigned6Bit::ONE,
+                u18!(0o000_123).reinterpret_as_signed(),
+                u36!(0o444_333_222_111),
+                0,
+            ),
+            (
+                u18!(0o222_111).reinterpret_as_signed(),
+                u36!(0o000_000_000_123)
+            )
+        );
+        assert_eq!(
+            simulate_exx_adx(
+                &context,
+                Opcode::Exx,
+                Unsigned6Bit::ONE,
+                u18!(0o777_776).reinterpret_as_signed(),
+                u36!(0o444_333_222_111),
+                0,
+            ),
+            (
+                u18!(0o222_111).reinterpret_as_signed(),
+                u36!(0o777_777_777_776)
+            )
+        );
+    }
+
+    #[test]
+    fn op_exx_half_words() {
+        let context = make_ctx();
+        // Standard configuration 1 is 340 (right half only).
+        assert_eq!(
+            simulate_exx_adx(
+                &context,
+                Opcode::Exx,
+                Unsigned6Bit::ONE,
+                u18!(0o000_123).reinterpret_as_signed(),
+                u36!(0o444_333_222_111),
+                1,
+            ),
+            (
+                u18!(0o222_111).reinterpret_as_signed(),
+                u36!(0o444_333_000_123)
+            )
+        );
+        // Standard configuration 2 is 342, which exchanges Xj with
+        // the left half of the memory word.
+        assert_eq!(
+            simulate_exx_adx(
+                &context,
+                Opcode::Exx,
+                Unsigned6Bit::ONE,
+                u18!(0o000_123).reinterpret_as_signed(),
+                u36!(0o444_333_222_111),
+                2,
+            ),
+            (
+                u18!(0o444_333).reinterpret_as_signed(),
+                u36!(0o000_123_222_111)
+            )
+        );
+    }
+
+    #[test]
+    fn op_exx_x0() {
+        let context = make_ctx();
+        // X₀ is always 0, so zero is stored and X₀ is unchanged.
+        assert_eq!(
+            simulate_exx_adx(
+                &context,
+                Opcode::Exx,
+                Unsigned6Bit::ZERO,
+                Signed18Bit::ZERO,
+                u36!(0o444_333_222_111),
+                1,
+            ),
+            (Signed18Bit::ZERO, u36!(0o444_333_000_000))
+        );
+    }
+
+    #[test]
+    fn op_exx_placeholder_keeps_mark_bit() {
+        let context = make_ctx();
+        // The mark bit of a placeholder is exchanged along with the
+        // address.
+        assert_eq!(
+            simulate_exx_adx(
+                &context,
+                Opcode::Exx,
+                u6!(0o41),
+                u18!(0o400_100).reinterpret_as_signed(),
+                u36!(0o444_333_000_200),
+                1,
+            ),
+            (
+                u18!(0o000_200).reinterpret_as_signed(),
+                u36!(0o444_333_400_100)
+            )
+        );
+    }
+
+    #[test]
+    fn op_adx_add() {
+        let context = make_ctx();
+        // 3 + 5 = 8 (the memory word is unchanged).
+        assert_eq!(
+            simulate_exx_adx(
+                &context,
+                Opcode::Adx,
+                Unsigned6Bit::ONE,
+                Signed18Bit::from(3_i8),
+                u36!(0o444_333_000_005),
+                1,
+            ),
+            (Signed18Bit::from(8_i8), u36!(0o444_333_000_005))
+        );
+        // 3 + -5 = -2.
+        assert_eq!(
+            simulate_exx_adx(
+                &context,
+                Opcode::Adx,
+                Unsigned6Bit::ONE,
+                Signed18Bit::from(3_i8),
+                u36!(0o777_772),
+                1,
+            )
+            .0,
+            Signed18Bit::from(-2_i8)
+        );
+        // Standard configuration 2 is 342, so the left half of the
+        // memory word is added.
+        assert_eq!(
+            simulate_exx_adx(
+                &context,
+                Opcode::Adx,
+                Unsigned6Bit::ONE,
+                Signed18Bit::from(3_i8),
+                u36!(0o000_010_000_005),
+                2,
+            )
+            .0,
+            u18!(0o000_013).reinterpret_as_signed()
+        );
+    }
+
+    #[test]
+    fn op_adx_wraps_around() {
+        let context = make_ctx();
+        // The largest positive value plus 1 wraps around to the
+        // largest negative value.
+        assert_eq!(
+            simulate_exx_adx(
+                &context,
+                Opcode::Adx,
+                Unsigned6Bit::ONE,
+                u18!(0o377_777).reinterpret_as_signed(),
+                u36!(1),
+                1,
+            )
+            .0,
+            u18!(0o400_000).reinterpret_as_signed()
+        );
+        // -1 + -1 = -2 (this requires an end-around carry).
+        assert_eq!(
+            simulate_exx_adx(
+                &context,
+                Opcode::Adx,
+                Unsigned6Bit::ONE,
+                u18!(0o777_776).reinterpret_as_signed(),
+                u36!(0o777_776),
+                1,
+            )
+            .0,
+            u18!(0o777_775).reinterpret_as_signed()
+        );
+    }
+
+    #[test]
+    fn op_adx_x0() {
+        let context = make_ctx();
+        assert_eq!(
+            simulate_exx_adx(
+                &context,
+                Opcode::Adx,
+                Unsigned6Bit::ZERO,
+                Signed18Bit::ZERO,
+                u36!(5),
+                1,
+            ),
+            (Signed18Bit::ZERO, u36!(5))
+        );
+    }
+
+    #[test]
+    fn op_adx_placeholder() {
+        let context = make_ctx();
+        // Adding to a marked placeholder changes the address and
+        // leaves the mark bit set.
+        assert_eq!(
+            simulate_exx_adx(
+                &context,
+                Opcode::Adx,
+                u6!(0o41),
+                u18!(0o400_100).reinterpret_as_signed(),
+                u36!(0o20),
+                1,
+            )
+            .0,
+            u18!(0o400_120).reinterpret_as_signed()
+        );
+        // As for EXX, the placeholder is an 18-bit value, so
+        // incrementing an unmarked placeholder past the top of
+        // memory sets the mark bit.
+        assert_eq!(
+            simulate_exx_adx(
+                &context,
+                Opcode::Adx,
+                u6!(0o41),
+                u18!(0o377_777).reinterpret_as_signed(),
+                u36!(1),
+                1,
+            )
+            .0,
+            u18!(0o400_000).reinterpret_as_signed()
+        );
+    }
 }
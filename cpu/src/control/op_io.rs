@@ -79,7 +79,9 @@ impl ControlUnit {
 
         let operand = self.regs.n.operand_address_and_defer_bit();
         let result = match u32::from(operand) {
-            0o20_000 => devices.disconnect(ctx, &j, &mut self.alarm_unit),
+            0o20_000 => devices
+                .disconnect(ctx, &j, &mut self.alarm_unit)
+                .map(|()| None),
             0o30_000..=0o37_777 => {
                 let mode: Unsigned12Bit = Unsigned12Bit::try_from(operand & 0o07_777).unwrap();
                 ControlUnit::connect_unit(
@@ -100,14 +102,14 @@ impl ControlUnit {
                     // Handbook page 4-7).
                     self.regs.current_sequence_is_runnable = true;
                 }
-                Ok(())
+                Ok(None)
             }
             0o50_000 => {
                 self.regs.flags.raise(&j);
                 if Some(j) == self.regs.k {
                     dismiss_reason = None;
                 }
-                Ok(())
+                Ok(None)
             }
             0o60_000..=0o60777 => {
                 // Select unit XXX
@@ -133,18 +135,19 @@ impl ControlUnit {
                     },
                 })?;
                 // IOSAL is masked.  Just do nothing.
-                Ok(())
+                Ok(None)
             }
         };
         if let Some(reason) = dismiss_reason {
             self.dismiss_unless_held(reason);
         }
-        result.map(|()| OpcodeResult {
+        result.map(|output| OpcodeResult {
             program_counter_change: None,
             // poll_order_change doesn't always need to be set, but
             // false positives cost us only compute efficiency.
             poll_order_change: Some(j),
-            output: None,
+            output,
+            arithmetic_work: None,
         })
     }
 
@@ -156,18 +159,22 @@ impl ControlUnit {
         unit: Unsigned6Bit,
         mode: Unsigned12Bit,
         alarm_unit: &mut AlarmUnit,
-    ) -> Result<(), Alarm> {
-        let maybe_flag_change: Option<FlagChange> = match u8::from(unit) {
+    ) -> Result<Option<OutputEvent>, Alarm> {
+        let (maybe_flag_change, output) = match u8::from(unit) {
+            0o41 => {
+                alarm_unit.set_program_alarm_mode(mode);
+                (None, None)
+            }
             0o42 => {
                 trap.connect(ctx, mode);
-                None
+                (None, None)
             }
             _ => devices.connect(ctx, regs.k, &unit, mode, alarm_unit)?,
         };
         if let Some(FlagChange::Raise) = maybe_flag_change {
             regs.flags.raise(&unit);
         }
-        Ok(())
+        Ok(output)
     }
 
     pub(crate) fn op_tsd(
@@ -197,6 +204,10 @@ impl ControlUnit {
                 MetaBitChange::None
             };
             // There are no sequence numbers below 0o40, besides 0.
+            //
+            // Unit 75 is also the miscellaneous output unit, but
+            // that unit takes its output from the IOS mode instead
+            // (see OPEN-QUESTIONS.md).
             if matches!(u8::from(unit), 0 | 0o75 | 0o76) {
                 // Non-INOUT sequences just cycle the target location;
                 // see section 4-1 (page 4-3) of the Users Handbook;
@@ -367,6 +378,7 @@ impl ControlUnit {
                     program_counter_change: None,
                     poll_order_change: self.regs.k,
                     output,
+                    arithmetic_work: None,
                 })
             }
             Ok(TransferOutcome::DismissAndWait) => {
@@ -383,6 +395,7 @@ impl ControlUnit {
                     )),
                     poll_order_change: self.regs.k,
                     output: None,
+                    arithmetic_work: None,
                 })
             }
             Err(e) => Err(e),
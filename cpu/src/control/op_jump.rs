@@ -3,6 +3,7 @@ use base::subword;
 
 use super::super::alarm::{Alarm, AlarmDetails, Alarmer, BadMemOp};
 use super::super::context::Context;
+use super::super::control::op_arith::active_subwords;
 use super::super::control::{ControlUnit, OpcodeResult, ProgramCounterChange};
 use super::super::exchanger::exchanged_value_for_load_without_sign_extension;
 use super::super::memory::{BitChange, MemoryMapped, MemoryOpFailure, MemoryUnit, WordChange};
@@ -11,9 +12,9 @@ use super::super::UpdateE;
 /// ## "Jump Skip Class" opcodes
 ///
 /// - JMP: [`ControlUnit::op_jmp`]
-/// - JPA (unimplemented)
-/// - JNA (unimplemented)
-/// - JOV (unimplemented)
+/// - JPA: [`ControlUnit::op_jpa`]
+/// - JNA: [`ControlUnit::op_jna`]
+/// - JOV: [`ControlUnit::op_jov`]
 /// - SKM: [`ControlUnit::op_skm`]
 /// - SED: [`ControlUnit::op_sed`]
 impl ControlUnit {
@@ -95,6 +96,79 @@ impl ControlUnit {
             program_counter_change: Some(ProgramCounterChange::Jump(new_pc)),
             poll_order_change: None,
             output: None,
+            arithmetic_work: None,
+        })
+    }
+
+    /// Implements the JPA instruction (Opcode 046).  Jumps to the
+    /// operand address if A is positive.  Only the sign bits of the
+    /// active subwords of A are examined, so +0 counts as positive
+    /// and -0 as negative.  When several subwords are active, JPA
+    /// jumps only if all of them are positive (hence exactly one of
+    /// JPA and JNA will jump).
+    pub(crate) fn op_jpa(
+        &mut self,
+        ctx: &Context,
+        mem: &mut MemoryUnit,
+    ) -> Result<OpcodeResult, Alarm> {
+        let negative = self.a_register_is_negative(mem);
+        self.jump_if(ctx, mem, !negative)
+    }
+
+    /// Implements the JNA instruction (Opcode 047).  Jumps to the
+    /// operand address if A is negative (that is, if the sign bit of
+    /// any active subword of A is set).  Minus zero counts as
+    /// negative.
+    pub(crate) fn op_jna(
+        &mut self,
+        ctx: &Context,
+        mem: &mut MemoryUnit,
+    ) -> Result<OpcodeResult, Alarm> {
+        let negative = self.a_register_is_negative(mem);
+        self.jump_if(ctx, mem, negative)
+    }
+
+    /// Implements the JOV instruction (Opcode 045).  Jumps to the
+    /// operand address if any active subword of A has overflowed
+    /// (see [`super::op_arith::OverflowLatch`]).  The overflow
+    /// indications of the active subwords are cleared.
+    pub(crate) fn op_jov(
+        &mut self,
+        ctx: &Context,
+        mem: &mut MemoryUnit,
+    ) -> Result<OpcodeResult, Alarm> {
+        let subwords = active_subwords(&self.get_config());
+        let overflowed = self.regs.overflow.test_and_clear(&subwords);
+        self.jump_if(ctx, mem, overflowed)
+    }
+
+    /// Determine whether the sign bit of any active subword of A is
+    /// set.
+    fn a_register_is_negative(&self, mem: &MemoryUnit) -> bool {
+        let a = u64::from(mem.get_a_register());
+        active_subwords(&self.get_config())
+            .iter()
+            .any(|subword| subword.extract(a) & (1 << (subword.width - 1)) != 0)
+    }
+
+    /// Jump to the operand address (which may be deferred and
+    /// indexed) if `condition` is true.
+    fn jump_if(
+        &mut self,
+        ctx: &Context,
+        mem: &mut MemoryUnit,
+        condition: bool,
+    ) -> Result<OpcodeResult, Alarm> {
+        let target: Address = self.operand_address_with_optional_defer_and_index(ctx, mem)?;
+        Ok(OpcodeResult {
+            program_counter_change: if condition {
+                Some(ProgramCounterChange::Jump(target))
+            } else {
+                None
+            },
+            poll_order_change: None,
+            output: None,
+            arithmetic_work: None,
         })
     }
 
@@ -189,6 +263,7 @@ impl ControlUnit {
             },
             poll_order_change: None,
             output: None,
+            arithmetic_work: None,
         })
     }
 
@@ -238,6 +313,7 @@ impl ControlUnit {
             },
             poll_order_change: None,
             output: None,
+            arithmetic_work: None,
         })
     }
 }
@@ -251,6 +327,7 @@ mod tests {
     };
     use super::super::super::memory::MetaBitChange;
     use super::super::super::{ControlUnit, MemoryConfiguration, MemoryUnit};
+    use super::super::tests::execute_single_instruction;
     use base::instruction::{Opcode, SymbolicInstruction};
     use base::prelude::*;
     use core::time::Duration;
@@ -318,6 +395,7 @@ mod tests {
                 program_counter_change: Some(ProgramCounterChange::Jump(to)),
                 poll_order_change: None,
                 output: None,
+                arithmetic_work: None,
             }) => {
                 let xj = control.regs.get_index_register(j);
                 let dismissed = !control.regs.flags.current_flag_state(&SequenceNumber::ZERO);
@@ -1035,4 +1113,149 @@ mod tests {
         // L(0o333_333_020_020) == R(0o070_070_333_333), so a skip should occur.
         assert!(!skipped, "SED instruction should not have skipped");
     }
+
+    /// Execute the instruction `inst` (which should be JPA, JNA,
+    /// JOV or ADD), returning the jump target if it jumped.
+    fn execute_arithmetic_or_jump(
+        ctx: &Context,
+        control: &mut ControlUnit,
+        mem: &mut MemoryUnit,
+        inst: &SymbolicInstruction,
+    ) -> Option<Address> {
+        match execute_single_instruction(ctx, control, mem, inst) {
+            Ok(OpcodeResult {
+                program_counter_change: Some(ProgramCounterChange::Jump(to)),
+                ..
+            }) => Some(to),
+            Ok(OpcodeResult {
+                program_counter_change: None,
+                ..
+            }) => None,
+            other => {
+                panic!(
+                    "{:?} didn't execute in the expected way: {other:?}",
+                    inst.opcode()
+                );
+            }
+        }
+    }
+
+    fn arithmetic_jump(opcode: Opcode, cfg: u8, target: Address) -> SymbolicInstruction {
+        SymbolicInstruction {
+            held: false,
+            configuration: Unsigned5Bit::try_from(cfg).expect("valid configuration"),
+            opcode,
+            index: Unsigned6Bit::ZERO,
+            operand_address: OperandAddress::Direct(target),
+        }
+    }
+
+    /// Determine whether JPA and JNA jump when A has the value `a`
+    /// and F-memory location `cfg` is used.
+    fn simulate_jpa_jna(a: Unsigned36Bit, cfg: u8) -> (bool, bool) {
+        let context = make_ctx();
+        let target = Address::from(u18!(0o3000));
+        let (mut control, mut mem) = setup(
+            &context,
+            Unsigned6Bit::ZERO,
+            Signed18Bit::ZERO,
+            Unsigned36Bit::ZERO,
+            Address::from(u18!(0o1000)),
+            Address::from(u18!(0o1000)),
+        );
+        mem.set_a_register(a);
+        let jpa = execute_arithmetic_or_jump(
+            &context,
+            &mut control,
+            &mut mem,
+            &arithmetic_jump(Opcode::Jpa, cfg, target),
+        );
+        let jna = execute_arithmetic_or_jump(
+            &context,
+            &mut control,
+            &mut mem,
+            &arithmetic_jump(Opcode::Jna, cfg, target),
+        );
+        for jumped_to in [jpa, jna].into_iter().flatten() {
+            assert_eq!(jumped_to, target);
+        }
+        (jpa.is_some(), jna.is_some())
+    }
+
+    #[test]
+    fn test_jpa_jna_full_word() {
+        // F-memory location 0 (configuration 000) is the full word.
+        assert_eq!(simulate_jpa_jna(u36!(1), 0), (true, false));
+        assert_eq!(simulate_jpa_jna(u36!(0o777_777_777_776), 0), (false, true));
+        // Plus zero is positive and minus zero is negative.
+        assert_eq!(simulate_jpa_jna(Unsigned36Bit::ZERO, 0), (true, false));
+        assert_eq!(simulate_jpa_jna(u36!(0o777_777_777_777), 0), (false, true));
+    }
+
+    #[test]
+    fn test_jpa_jna_subwords() {
+        // F-memory location 1 is configuration 340 (right half only),
+        // so the sign of the left half is not considered.
+        assert_eq!(simulate_jpa_jna(u36!(0o400_000_000_001), 1), (true, false));
+        assert_eq!(simulate_jpa_jna(u36!(0o000_000_400_000), 1), (false, true));
+        // F-memory location 16 is configuration 200 (both halves).
+        // A is negative if either half is negative.
+        assert_eq!(simulate_jpa_jna(u36!(0o000_001_000_001), 16), (true, false));
+        assert_eq!(simulate_jpa_jna(u36!(0o000_001_400_000), 16), (false, true));
+        assert_eq!(simulate_jpa_jna(u36!(0o400_000_000_001), 16), (false, true));
+    }
+
+    #[test]
+    fn test_jov() {
+        let context = make_ctx();
+        let operand_address = Address::from(u18!(0o100));
+        let target = Address::from(u18!(0o3000));
+        let (mut control, mut mem) = setup(
+            &context,
+            Unsigned6Bit::ZERO,
+            Signed18Bit::ZERO,
+            Unsigned36Bit::ZERO,
+            Address::from(u18!(0o1000)),
+            Address::from(u18!(0o1000)),
+        );
+        control
+            .memory_store_without_exchange(
+                &context,
+                &mut mem,
+                &operand_address,
+                &u36!(1),
+                &UpdateE::No,
+                &MetaBitChange::None,
+            )
+            .expect("failed to set up JOV test data");
+        let jov = |control: &mut ControlUnit, mem: &mut MemoryUnit, cfg: u8| {
+            execute_arithmetic_or_jump(
+                &context,
+                control,
+                mem,
+                &arithmetic_jump(Opcode::Jov, cfg, target),
+            )
+        };
+
+        // No overflow has happened yet.
+        assert_eq!(jov(&mut control, &mut mem, 0), None);
+
+        // Cause the right half to overflow.  F-memory location 16 is
+        // configuration 200 (both halves).
+        mem.set_a_register(u36!(0o000_000_377_777));
+        execute_arithmetic_or_jump(
+            &context,
+            &mut control,
+            &mut mem,
+            &arithmetic_jump(Opcode::Add, 16, operand_address),
+        );
+        // F-memory location 29 is configuration 330 (the left half
+        // only); the left half did not overflow.
+        assert_eq!(jov(&mut control, &mut mem, 29), None);
+        // F-memory location 1 is configuration 340 (right half only).
+        assert_eq!(jov(&mut control, &mut mem, 1), Some(target));
+        // JOV cleared the overflow indication.
+        assert_eq!(jov(&mut control, &mut mem, 1), None);
+        assert_eq!(jov(&mut control, &mut mem, 0), None);
+    }
 }
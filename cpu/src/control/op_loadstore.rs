@@ -9,14 +9,15 @@
 //! - STC: [`ControlUnit::op_stc`]
 //! - STD: [`ControlUnit::op_std`]
 //! - STE: [`ControlUnit::op_ste`]
-//! - EXA: (unimplemented)
+//! - EXA: [`ControlUnit::op_exa`]
+//! - INS: [`ControlUnit::op_ins`]
 
 use tracing::{event, Level};
 
 use super::alarm::Alarm;
 use super::context::Context;
 use super::control::{ControlUnit, MemoryUnit, OpcodeResult, UpdateE};
-use super::exchanger::exchanged_value_for_load;
+use super::exchanger::{exchanged_value_for_load, exchanged_value_for_load_without_sign_extension};
 use base::prelude::*;
 
 impl ControlUnit {
@@ -138,6 +139,67 @@ impl ControlUnit {
         self.op_store_ae_register(ctx, mem.get_e_register(), mem, &UpdateE::No)
     }
 
+    /// Implements the EXA instruction (Opcode 054).  The active
+    /// quarters of the A register are exchanged with the
+    /// corresponding (permuted) quarters of the operand.  That is,
+    /// the operand is loaded into A as for LDA while the original
+    /// value of A is stored into the operand as for STA.
+    pub(crate) fn op_exa(
+        &mut self,
+        ctx: &Context,
+        mem: &mut MemoryUnit,
+    ) -> Result<OpcodeResult, Alarm> {
+        let target: Address = self.operand_address_with_optional_defer_and_index(ctx, mem)?;
+        let a = mem.get_a_register();
+        let mut memword: Option<Unsigned36Bit> = None;
+        self.memory_read_and_update_with_exchange(ctx, mem, &target, &UpdateE::Yes, |existing| {
+            memword = Some(existing);
+            a
+        })?;
+        match memword {
+            Some(memword) => {
+                let new_a = exchanged_value_for_load(&self.get_config(), &memword, &a);
+                event!(
+                    Level::TRACE,
+                    "EXA: A={a:o} stored at {target:o}, A is now {new_a:o}"
+                );
+                mem.set_a_register(new_a);
+            }
+            None => {
+                // The read failed but QSAL is masked, so the
+                // instruction has no effect.
+            }
+        }
+        Ok(OpcodeResult::default())
+    }
+
+    /// Implements the INS instruction (Opcode 055).  Those bits of
+    /// the active quarters of the operand which correspond to ones in
+    /// the E register are replaced by the corresponding bits of A.
+    /// Neither A nor E is changed.
+    pub(crate) fn op_ins(
+        &mut self,
+        ctx: &Context,
+        mem: &mut MemoryUnit,
+    ) -> Result<OpcodeResult, Alarm> {
+        let target: Address = self.operand_address_with_optional_defer_and_index(ctx, mem)?;
+        let a = mem.get_a_register();
+        let mask = mem.get_e_register();
+        let cfg = self.get_config();
+        self.memory_read_and_update_with_exchange(ctx, mem, &target, &UpdateE::No, |existing| {
+            // Line the memory word up with A and E; the store
+            // operation will permute the result back again.
+            let current = exchanged_value_for_load_without_sign_extension(&cfg, &existing, &a);
+            let inserted = (current & !mask) | (a & mask);
+            event!(
+                Level::TRACE,
+                "INS: inserting {a:o} under mask {mask:o} into {current:o} gives {inserted:o}"
+            );
+            inserted
+        })
+        .map(|()| OpcodeResult::default())
+    }
+
     /// Implement opcodes ST{A,B,C,D,E}.
     fn op_store_ae_register(
         &mut self,
@@ -176,6 +238,7 @@ mod tests {
     use super::super::super::context::Context;
     use super::super::super::control::ConfigurationMemorySetup;
     use super::super::super::exchanger::SystemConfiguration;
+    use super::super::super::io::Unit;
     use super::super::super::memory::{MemoryMapped, MetaBitChange};
     use super::super::super::{MemoryConfiguration, MemoryUnit};
     use super::super::{ControlUnit, PanicOnUnmaskedAlarm, UpdateE};
@@ -405,6 +468,8 @@ mod tests {
             .expect(&complain);
         let f = match opcode {
             Opcode::Ste => ControlUnit::op_ste,
+            Opcode::Exa => ControlUnit::op_exa,
+            Opcode::Ins => ControlUnit::op_ins,
             _ => {
                 panic!("opcode {:?} is not yet supported", opcode);
             }
@@ -682,4 +747,159 @@ mod tests {
         assert_eq!(expected, expected2, "test is internally inconsistent");
         assert_eq!(result, expected, "incorrect value stored at 0o100");
     }
+
+    #[test]
+    fn test_exa_full_word() {
+        let context = make_ctx();
+        let working_address: Address = Address::from(u18!(0o100));
+        let (mut control, mut mem) =
+            set_up_store(&context, u36!(0o444_333_222_111), &working_address, |mem| {
+                mem.set_a_register(u36!(0o004_003_002_001));
+            });
+        let (result, e) = simulate_store(
+            &context,
+            &mut control,
+            &mut mem,
+            &working_address,
+            Unsigned6Bit::ZERO, // no indexing
+            Opcode::Exa,
+            false,
+            SystemConfiguration::from(Unsigned9Bit::ZERO),
+        );
+        assert_eq!(result, u36!(0o004_003_002_001), "incorrect value stored");
+        assert_eq!(mem.get_a_register(), u36!(0o444_333_222_111), "incorrect A");
+        assert_eq!(e, result, "E should contain the stored value");
+    }
+
+    #[test]
+    fn test_exa_right_half() {
+        let context = make_ctx();
+        let working_address: Address = Address::from(u18!(0o100));
+        let (mut control, mut mem) =
+            set_up_store(&context, u36!(0o444_333_222_111), &working_address, |mem| {
+                mem.set_a_register(u36!(0o004_003_002_001));
+            });
+        // Configuration 340 (standard configuration 1) exchanges
+        // only the right halves.
+        let (result, _e) = simulate_store(
+            &context,
+            &mut control,
+            &mut mem,
+            &working_address,
+            Unsigned6Bit::ZERO, // no indexing
+            Opcode::Exa,
+            false,
+            SystemConfiguration::from(u9!(0o340)),
+        );
+        assert_eq!(result, u36!(0o444_333_002_001), "incorrect value stored");
+        assert_eq!(mem.get_a_register(), u36!(0o004_003_222_111), "incorrect A");
+    }
+
+    #[test]
+    fn test_exa_sets_operand_metabit() {
+        let context = make_ctx();
+        let working_address: Address = Address::from(u18!(0o100));
+        let (mut control, mut mem) =
+            set_up_store(&context, u36!(0o444_333_222_111), &working_address, |_| {});
+        // Set the trap circuit to set the metabits of operands.
+        control.trap.connect(
+            &context,
+            Unsigned12Bit::try_from(0o400_u16).expect("valid trap mode"),
+        );
+        simulate_store(
+            &context,
+            &mut control,
+            &mut mem,
+            &working_address,
+            Unsigned6Bit::ZERO, // no indexing
+            Opcode::Exa,
+            false,
+            SystemConfiguration::from(Unsigned9Bit::ZERO),
+        );
+        match mem.fetch(&context, &working_address, &MetaBitChange::None) {
+            Ok((_, extra)) => {
+                assert!(extra.meta, "EXA should have set the metabit of its operand");
+            }
+            Err(e) => {
+                panic!("unable to retrieve the stored word: {}", e);
+            }
+        }
+    }
+
+    #[test]
+    fn test_ins_full_word() {
+        let context = make_ctx();
+        let working_address: Address = Address::from(u18!(0o100));
+        let mask = u36!(0o000_777_777_000);
+        let (mut control, mut mem) =
+            set_up_store(&context, u36!(0o111_111_111_111), &working_address, |mem| {
+                mem.set_a_register(u36!(0o777_000_777_000));
+                mem.set_e_register(mask);
+            });
+        let (result, e) = simulate_store(
+            &context,
+            &mut control,
+            &mut mem,
+            &working_address,
+            Unsigned6Bit::ZERO, // no indexing
+            Opcode::Ins,
+            false,
+            SystemConfiguration::from(Unsigned9Bit::ZERO),
+        );
+        assert_eq!(result, u36!(0o111_000_777_111), "incorrect value stored");
+        assert_eq!(e, mask, "E should be unchanged");
+        assert_eq!(
+            mem.get_a_register(),
+            u36!(0o777_000_777_000),
+            "A should be unchanged"
+        );
+    }
+
+    #[test]
+    fn test_ins_right_half() {
+        let context = make_ctx();
+        let working_address: Address = Address::from(u18!(0o100));
+        let (mut control, mut mem) =
+            set_up_store(&context, u36!(0o111_111_111_111), &working_address, |mem| {
+                mem.set_a_register(u36!(0o777_000_777_000));
+                mem.set_e_register(u36!(0o000_777_777_000));
+            });
+        // Configuration 340 (standard configuration 1) changes only
+        // the right half of the operand.
+        let (result, _e) = simulate_store(
+            &context,
+            &mut control,
+            &mut mem,
+            &working_address,
+            Unsigned6Bit::ZERO, // no indexing
+            Opcode::Ins,
+            false,
+            SystemConfiguration::from(u9!(0o340)),
+        );
+        assert_eq!(result, u36!(0o111_111_777_111), "incorrect value stored");
+    }
+
+    #[test]
+    fn test_ins_permuted() {
+        let context = make_ctx();
+        let working_address: Address = Address::from(u18!(0o100));
+        let (mut control, mut mem) =
+            set_up_store(&context, u36!(0o111_111_111_111), &working_address, |mem| {
+                mem.set_a_register(u36!(0o777_000_777_000));
+                mem.set_e_register(u36!(0o000_777_777_000));
+            });
+        // Configuration 342 (standard configuration 2) inserts bits
+        // of R(A), under the mask in R(E), into L(100).
+        let (result, _e) = simulate_store(
+            &context,
+            &mut control,
+            &mut mem,
+            &working_address,
+            Unsigned6Bit::ZERO, // no indexing
+            Opcode::Ins,
+            false,
+            SystemConfiguration::from(u9!(0o342)),
+        );
+        assert_eq!(result, u36!(0o777_111_111_111), "incorrect value stored");
+    }
 }
@@ -0,0 +1,344 @@
+//! Implementations of "Logical Class" opcodes
+//! - ITE: [`ControlUnit::op_ite`]
+//! - ITA: [`ControlUnit::op_ita`]
+//! - UNA: [`ControlUnit::op_una`]
+//! - COM: [`ControlUnit::op_com`]
+//! - DSA: [`ControlUnit::op_dsa`]
+//!
+//! The operand of each of these instructions is passed through the
+//! exchange element (so it is permuted and sign-extended according
+//! to the current system configuration) and is then combined bit by
+//! bit with the E or A register.  Only the active quarters of the
+//! destination register are changed.
+
+use tracing::{event, Level};
+
+use base::prelude::*;
+
+use super::alarm::Alarm;
+use super::context::Context;
+use super::control::{ControlUnit, OpcodeResult, UpdateE};
+use super::exchanger::SystemConfiguration;
+use super::memory::MemoryUnit;
+use super::op_arith::{active_subwords, to_word};
+
+/// Replace the active quarters of `dest` (under the system
+/// configuration `cfg`) with the corresponding bits of `value`.
+fn update_active_quarters(
+    cfg: &SystemConfiguration,
+    dest: Unsigned36Bit,
+    value: Unsigned36Bit,
+) -> Unsigned36Bit {
+    let value = u64::from(value);
+    to_word(
+        active_subwords(cfg)
+            .iter()
+            .fold(u64::from(dest), |result, subword| {
+                subword.insert(result, subword.extract(value))
+            }),
+    )
+}
+
+/// ## "Logical Class" opcodes
+///
+/// - ITE: [`ControlUnit::op_ite`]
+/// - ITA: [`ControlUnit::op_ita`]
+/// - UNA: [`ControlUnit::op_una`]
+/// - COM: [`ControlUnit::op_com`]
+/// - DSA: [`ControlUnit::op_dsa`]
+impl ControlUnit {
+    /// Implements the ITE instruction (Opcode 040).  The E register
+    /// is replaced by the intersection (bitwise AND) of E and the
+    /// (exchanged) operand.
+    ///
+    /// ITE is normally held (see [`Opcode::hold_is_implicit`]); the
+    /// assembler sets the hold bit of the instruction word, so that
+    /// no change of sequence takes place before the following
+    /// instruction (which will usually make use of E) is executed.
+    pub(crate) fn op_ite(
+        &mut self,
+        ctx: &Context,
+        mem: &mut MemoryUnit,
+    ) -> Result<OpcodeResult, Alarm> {
+        let target: Address = self.operand_address_with_optional_defer_and_index(ctx, mem)?;
+        let e = mem.get_e_register();
+        let (operand, _extra) =
+            self.fetch_operand_from_address_with_exchange(ctx, mem, &target, &e, &UpdateE::No)?;
+        let result = update_active_quarters(&self.get_config(), e, e & operand);
+        event!(Level::TRACE, "ITE: {e:o} & {operand:o} = {result:o}");
+        mem.set_e_register(result);
+        Ok(OpcodeResult::default())
+    }
+
+    /// Implements the ITA instruction (Opcode 041).  The A register
+    /// is replaced by the intersection (bitwise AND) of A and the
+    /// (exchanged) operand.
+    pub(crate) fn op_ita(
+        &mut self,
+        ctx: &Context,
+        mem: &mut MemoryUnit,
+    ) -> Result<OpcodeResult, Alarm> {
+        self.combine_with_a_register(ctx, mem, "ITA", |a, operand| a & operand)
+    }
+
+    /// Implements the UNA instruction (Opcode 042).  The A register
+    /// is replaced by the union (bitwise OR) of A and the (exchanged)
+    /// operand.
+    pub(crate) fn op_una(
+        &mut self,
+        ctx: &Context,
+        mem: &mut MemoryUnit,
+    ) -> Result<OpcodeResult, Alarm> {
+        self.combine_with_a_register(ctx, mem, "UNA", |a, operand| a | operand)
+    }
+
+    /// Implements the COM instruction (Opcode 056).  The A register
+    /// is replaced by the complement of the (exchanged) operand.
+    pub(crate) fn op_com(
+        &mut self,
+        ctx: &Context,
+        mem: &mut MemoryUnit,
+    ) -> Result<OpcodeResult, Alarm> {
+        self.combine_with_a_register(ctx, mem, "COM", |_, operand| !operand)
+    }
+
+    /// Implements the DSA instruction (Opcode 065).  The A register
+    /// is replaced by the "dot sum" (bitwise exclusive OR) of A and
+    /// the (exchanged) operand.
+    pub(crate) fn op_dsa(
+        &mut self,
+        ctx: &Context,
+        mem: &mut MemoryUnit,
+    ) -> Result<OpcodeResult, Alarm> {
+        self.combine_with_a_register(ctx, mem, "DSA", |a, operand| a ^ operand)
+    }
+
+    /// Fetch the (exchanged) operand, copying the memory word into
+    /// E, and replace the active quarters of A by `op(A, operand)`.
+    fn combine_with_a_register<F>(
+        &mut self,
+        ctx: &Context,
+        mem: &mut MemoryUnit,
+        name: &str,
+        op: F,
+    ) -> Result<OpcodeResult, Alarm>
+    where
+        F: Fn(Unsigned36Bit, Unsigned36Bit) -> Unsigned36Bit,
+    {
+        let target: Address = self.operand_address_with_optional_defer_and_index(ctx, mem)?;
+        let a = mem.get_a_register();
+        let (operand, _extra) =
+            self.fetch_operand_from_address_with_exchange(ctx, mem, &target, &a, &UpdateE::Yes)?;
+        let result = update_active_quarters(&self.get_config(), a, op(a, operand));
+        event!(
+            Level::TRACE,
+            "{name}: A={a:o}, operand={operand:o}, result={result:o}"
+        );
+        mem.set_a_register(result);
+        Ok(OpcodeResult::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::control::ConfigurationMemorySetup;
+    use super::super::super::memory::MetaBitChange;
+    use super::super::super::{MemoryConfiguration, MemoryUnit};
+    use super::super::tests::{execute_single_instruction, make_ctx};
+    use super::super::{ControlUnit, PanicOnUnmaskedAlarm, UpdateE};
+    use base::prelude::*;
+
+    const COMPLAIN: &str = "failed to set up logical test data";
+    const OPERAND_ADDRESS: Address = Address::new(u18!(0o100));
+
+    /// Execute a logical instruction with configuration `cfg_index`
+    /// (which selects a location in the standard F-memory setup),
+    /// returning the resulting values of A and E.
+    fn simulate(
+        opcode: Opcode,
+        cfg_index: u8,
+        a: Unsigned36Bit,
+        e: Unsigned36Bit,
+        operand: Unsigned36Bit,
+    ) -> (Unsigned36Bit, Unsigned36Bit) {
+        let ctx = make_ctx();
+        let mut control = ControlUnit::new(
+            PanicOnUnmaskedAlarm::Yes,
+            ConfigurationMemorySetup::StandardForTestingOnly,
+        );
+        let mut mem = MemoryUnit::new(
+            &ctx,
+            &MemoryConfiguration {
+                with_u_memory: false,
+            },
+        );
+        control
+            .memory_store_without_exchange(
+                &ctx,
+                &mut mem,
+                &OPERAND_ADDRESS,
+                &operand,
+                &UpdateE::No,
+                &MetaBitChange::None,
+            )
+            .expect(COMPLAIN);
+        mem.set_a_register(a);
+        mem.set_e_register(e);
+        let inst = SymbolicInstruction {
+            held: opcode.hold_is_implicit(),
+            configuration: Unsigned5Bit::try_from(cfg_index).expect(COMPLAIN),
+            opcode,
+            index: Unsigned6Bit::ZERO,
+            operand_address: OperandAddress::Direct(OPERAND_ADDRESS),
+        };
+        let result = execute_single_instruction(&ctx, &mut control, &mut mem, &inst);
+        if let Err(e) = result {
+            panic!("{:?} instruction failed: {}", opcode, e);
+        }
+        (mem.get_a_register(), mem.get_e_register())
+    }
+
+    #[test]
+    fn test_ite() {
+        // F-memory location 0 (configuration 000) is the full word.
+        assert_eq!(
+            simulate(
+                Opcode::Ite,
+                0,
+                u36!(0o123),
+                u36!(0o707_070_707_070),
+                u36!(0o777_000_777_000)
+            ),
+            (u36!(0o123), u36!(0o707_000_707_000))
+        );
+        // F-memory location 1 is configuration 340 (right half
+        // only).  The left half of E is unchanged.
+        assert_eq!(
+            simulate(
+                Opcode::Ite,
+                1,
+                u36!(0o123),
+                u36!(0o707_070_707_070),
+                u36!(0o000_000_777_000)
+            ),
+            (u36!(0o123), u36!(0o707_070_707_000))
+        );
+    }
+
+    #[test]
+    fn test_ita() {
+        assert_eq!(
+            simulate(
+                Opcode::Ita,
+                0,
+                u36!(0o707_070_707_070),
+                u36!(0o123),
+                u36!(0o777_000_777_000)
+            ),
+            (u36!(0o707_000_707_000), u36!(0o777_000_777_000))
+        );
+    }
+
+    #[test]
+    fn test_una() {
+        assert_eq!(
+            simulate(
+                Opcode::Una,
+                0,
+                u36!(0o707_070_707_070),
+                u36!(0o123),
+                u36!(0o000_007_000_007)
+            ),
+            (u36!(0o707_077_707_077), u36!(0o000_007_000_007))
+        );
+        // F-memory location 29 is configuration 330 (left half
+        // only).
+        assert_eq!(
+            simulate(
+                Opcode::Una,
+                29,
+                u36!(0o707_070_707_070),
+                u36!(0o123),
+                u36!(0o000_007_000_007)
+            ),
+            (u36!(0o707_077_707_070), u36!(0o000_007_000_007))
+        );
+    }
+
+    #[test]
+    fn test_com() {
+        assert_eq!(
+            simulate(
+                Opcode::Com,
+                0,
+                u36!(0o555),
+                u36!(0o123),
+                u36!(0o707_070_707_070)
+            ),
+            (u36!(0o070_707_070_707), u36!(0o707_070_707_070))
+        );
+        // F-memory location 16 is configuration 200 (halves); all
+        // quarters are active.
+        assert_eq!(
+            simulate(
+                Opcode::Com,
+                16,
+                u36!(0o555),
+                u36!(0o123),
+                u36!(0o707_070_707_070)
+            ),
+            (u36!(0o070_707_070_707), u36!(0o707_070_707_070))
+        );
+        // F-memory location 1 is configuration 340 (right half
+        // only).  The left half of A is unchanged.
+        assert_eq!(
+            simulate(
+                Opcode::Com,
+                1,
+                u36!(0o555_555_555_555),
+                u36!(0o123),
+                u36!(0o000_000_707_070)
+            ),
+            (u36!(0o555_555_070_707), u36!(0o000_000_707_070))
+        );
+    }
+
+    #[test]
+    fn test_dsa() {
+        assert_eq!(
+            simulate(
+                Opcode::Dsa,
+                0,
+                u36!(0o707_070_707_070),
+                u36!(0o123),
+                u36!(0o777_000_777_000)
+            ),
+            (u36!(0o070_070_070_070), u36!(0o777_000_777_000))
+        );
+        // F-memory location 1 is configuration 340 (right half
+        // only).  The inactive left half of A is unchanged (it is
+        // not combined with itself).
+        assert_eq!(
+            simulate(
+                Opcode::Dsa,
+                1,
+                u36!(0o707_070_707_070),
+                u36!(0o123),
+                u36!(0o000_000_777_000)
+            ),
+            (u36!(0o707_070_070_070), u36!(0o000_000_777_000))
+        );
+        // F-memory location 24 is configuration 600 (quarters); the
+        // operand is permuted in the same way as for LDA.
+        assert_eq!(
+            simulate(
+                Opcode::Dsa,
+                24,
+                u36!(0o707_070_707_070),
+                u36!(0o123),
+                u36!(0o777_000_777_000)
+            ),
+            (u36!(0o070_070_070_070), u36!(0o777_000_777_000))
+        );
+    }
+}
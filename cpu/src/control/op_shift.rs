@@ -0,0 +1,1135 @@
+//! Implementations of the cycle, normalize and scale opcodes
+//! - CYA: [`ControlUnit::op_cya`]
+//! - CYB: [`ControlUnit::op_cyb`]
+//! - CAB: [`ControlUnit::op_cab`]
+//! - NOA: [`ControlUnit::op_noa`]
+//! - NAB: [`ControlUnit::op_nab`]
+//! - SCA: [`ControlUnit::op_sca`]
+//! - SCB: [`ControlUnit::op_scb`]
+//! - SAB: [`ControlUnit::op_sab`]
+//!
+//! These instructions have no memory operand.  For the cycle and
+//! scale instructions the (deferred and indexed) operand address
+//! specifies the number of places by which to shift.  The normalize
+//! instructions instead store the number of places shifted in an
+//! index register.  As for the arithmetic instructions, each active
+//! subword is shifted separately.
+
+use tracing::{event, Level};
+
+use base::prelude::*;
+
+use super::alarm::Alarm;
+use super::context::Context;
+use super::control::{ControlUnit, OpcodeResult};
+use super::memory::MemoryUnit;
+use super::op_arith::{active_subwords, to_word, OverflowLatch, Subword};
+
+/// Cycle the `width`-bit value `value` left by `count` places.
+/// Negative values of `count` cycle to the right.
+fn cycle(value: u128, width: u32, count: i32) -> u128 {
+    let mask: u128 = (1 << width) - 1;
+    let left: u32 = count.rem_euclid(width as i32) as u32;
+    if left == 0 {
+        value
+    } else {
+        ((value << left) | (value >> (width - left))) & mask
+    }
+}
+
+/// Cycle each active subword of `word` by `count` places.
+fn cycle_by_subwords(subwords: &[Subword], word: Unsigned36Bit, count: i32) -> Unsigned36Bit {
+    let word = u64::from(word);
+    let result = subwords.iter().fold(word, |result, subword| {
+        let cycled = cycle(u128::from(subword.extract(word)), subword.width, count);
+        subword.insert(
+            result,
+            u64::try_from(cycled).expect("cycled value should fit (this is a bug)"),
+        )
+    });
+    to_word(result)
+}
+
+/// Normalize the `width`-bit one's complement value `value`; that
+/// is, shift it left until its two most significant bits differ.
+/// Bits shifted in at the right are copies of the sign bit.  Returns
+/// the shifted value and the number of places shifted.
+///
+/// Zero and minus zero cannot be normalized; they are returned
+/// unchanged, with a shift count of zero.
+fn normalize(value: u128, width: u32) -> (u128, u32) {
+    let mask: u128 = (1 << width) - 1;
+    let sign: u128 = 1 << (width - 1);
+    let fill: u128 = if value & sign != 0 { 1 } else { 0 };
+    if value == 0 || value == mask {
+        return (value, 0);
+    }
+    let mut value = value;
+    let mut count = 0;
+    while ((value >> (width - 1)) & 1) == ((value >> (width - 2)) & 1) {
+        value = ((value << 1) | fill) & mask;
+        count += 1;
+    }
+    (value, count)
+}
+
+/// Scale (that is, arithmetically shift) the `width`-bit one's
+/// complement value `value` left by `count` places.  Negative values
+/// of `count` scale to the right.  The sign bit is unchanged, and the
+/// bits shifted in (at the right for a left scale, at the left for a
+/// right scale) are copies of it.  Hence negative values remain
+/// negative and -0 remains -0.
+///
+/// Returns the scaled value and an indication of whether a left
+/// scale lost significant bits (that is, bits which differ from the
+/// sign bit were shifted out).  Bits shifted out by a right scale are
+/// simply lost.
+fn scale(value: u128, width: u32, count: i32) -> (u128, bool) {
+    let sign: u128 = 1 << (width - 1);
+    let magnitude_mask: u128 = sign - 1;
+    let negative = value & sign != 0;
+    let fill: u128 = if negative { magnitude_mask } else { 0 };
+    let magnitude = value & magnitude_mask;
+    // Shifting by width-1 places or more leaves only copies of the
+    // sign bit.
+    let places: u32 = count.unsigned_abs().min(width - 1);
+    if places == 0 {
+        return (value, false);
+    }
+    let (shifted, overflow) = if count > 0 {
+        let lost = magnitude >> (width - 1 - places);
+        let lost_mask: u128 = (1 << places) - 1;
+        let shifted = ((magnitude << places) | (fill >> (width - 1 - places))) & magnitude_mask;
+        (shifted, lost != (fill & lost_mask))
+    } else {
+        let shifted = ((magnitude >> places) | (fill << (width - 1 - places))) & magnitude_mask;
+        (shifted, false)
+    };
+    ((value & sign) | shifted, overflow)
+}
+
+/// Join the subwords `a` and `b` (each `width` bits wide) of the A
+/// and B registers into the double-length one's complement value
+/// they represent.  This has the layout produced by MUL: the sign is
+/// the sign bit of `a`, and the 2(w-1)-bit magnitude is made up of
+/// the other bits of `a` followed by all but the sign bit of `b`.
+/// The sign bit of `b` is ignored.  The result is 2w-1 bits wide.
+fn join_double_length(a: u64, b: u64, width: u32) -> u128 {
+    let low_mask: u64 = (1 << (width - 1)) - 1;
+    (u128::from(a) << (width - 1)) | u128::from(b & low_mask)
+}
+
+/// Split the double-length value `value` (see
+/// [`join_double_length`]) into the subwords of A and B.  The sign
+/// bit of the B subword is set to the sign of `value`.
+fn split_double_length(value: u128, width: u32) -> (u64, u64) {
+    let low_mask: u128 = (1 << (width - 1)) - 1;
+    let a = u64::try_from(value >> (width - 1)).expect("subword should fit (this is a bug)");
+    let sign: u64 = a >> (width - 1);
+    let b = u64::try_from(value & low_mask).expect("subword should fit (this is a bug)")
+        | (sign << (width - 1));
+    (a, b)
+}
+
+/// ## Cycle, normalize and scale opcodes
+///
+/// - CYA: [`ControlUnit::op_cya`]
+/// - CYB: [`ControlUnit::op_cyb`]
+/// - CAB: [`ControlUnit::op_cab`]
+/// - NOA: [`ControlUnit::op_noa`]
+/// - NAB: [`ControlUnit::op_nab`]
+/// - SCA: [`ControlUnit::op_sca`]
+/// - SCB: [`ControlUnit::op_scb`]
+/// - SAB: [`ControlUnit::op_sab`]
+impl ControlUnit {
+    /// Implements the CYA instruction (Opcode 060).  Each active
+    /// subword of A is cycled left by the number of places given by
+    /// the operand address (cycling right if this is negative).
+    pub(crate) fn op_cya(
+        &mut self,
+        ctx: &Context,
+        mem: &mut MemoryUnit,
+    ) -> Result<OpcodeResult, Alarm> {
+        let count = self.shift_count(ctx, mem)?;
+        let subwords = active_subwords(&self.get_config());
+        mem.set_a_register(cycle_by_subwords(&subwords, mem.get_a_register(), count));
+        Ok(OpcodeResult::default())
+    }
+
+    /// Implements the CYB instruction (Opcode 061).  This is the same
+    /// as CYA, but operates on B.
+    pub(crate) fn op_cyb(
+        &mut self,
+        ctx: &Context,
+        mem: &mut MemoryUnit,
+    ) -> Result<OpcodeResult, Alarm> {
+        let count = self.shift_count(ctx, mem)?;
+        let subwords = active_subwords(&self.get_config());
+        mem.set_b_register(cycle_by_subwords(&subwords, mem.get_b_register(), count));
+        Ok(OpcodeResult::default())
+    }
+
+    /// Implements the CAB instruction (Opcode 062).  Each active
+    /// subword of A is joined with the corresponding subword of B
+    /// (with A on the left) and the resulting double-length value is
+    /// cycled, as for CYA.
+    pub(crate) fn op_cab(
+        &mut self,
+        ctx: &Context,
+        mem: &mut MemoryUnit,
+    ) -> Result<OpcodeResult, Alarm> {
+        let count = self.shift_count(ctx, mem)?;
+        let a = u64::from(mem.get_a_register());
+        let b = u64::from(mem.get_b_register());
+        let (mut new_a, mut new_b) = (a, b);
+        for subword in active_subwords(&self.get_config()) {
+            let w = subword.width;
+            let joined: u128 =
+                (u128::from(subword.extract(a)) << w) | u128::from(subword.extract(b));
+            let cycled = cycle(joined, 2 * w, count);
+            let mask = u128::from(subword.mask());
+            new_a = subword.insert(
+                new_a,
+                u64::try_from((cycled >> w) & mask).expect("subword should fit (this is a bug)"),
+            );
+            new_b = subword.insert(
+                new_b,
+                u64::try_from(cycled & mask).expect("subword should fit (this is a bug)"),
+            );
+        }
+        mem.set_a_register(to_word(new_a));
+        mem.set_b_register(to_word(new_b));
+        Ok(OpcodeResult::default())
+    }
+
+    /// Implements the NOA instruction (Opcode 064).  Each active
+    /// subword of A is normalized (see [`normalize`]).  The number of
+    /// places by which the most significant active subword was
+    /// shifted is stored in the index register specified by the
+    /// instruction (unless this is X₀, which is always zero).
+    pub(crate) fn op_noa(
+        &mut self,
+        _ctx: &Context,
+        mem: &mut MemoryUnit,
+    ) -> Result<OpcodeResult, Alarm> {
+        let a = u64::from(mem.get_a_register());
+        let mut new_a = a;
+        let mut counts: Vec<u32> = Vec::new();
+        for subword in active_subwords(&self.get_config()) {
+            let (normalized, count) = normalize(u128::from(subword.extract(a)), subword.width);
+            new_a = subword.insert(
+                new_a,
+                u64::try_from(normalized).expect("subword should fit (this is a bug)"),
+            );
+            counts.push(count);
+        }
+        mem.set_a_register(to_word(new_a));
+        self.store_normalize_count(counts.first().copied());
+        Ok(OpcodeResult::default())
+    }
+
+    /// Implements the NAB instruction (Opcode 066).  This is the same
+    /// as NOA, except that each active subword of A is joined with
+    /// the corresponding subword of B to form a double-length value
+    /// (see [`join_double_length`]), which is normalized.  The sign
+    /// bit of the B subword is then set from the sign of the result.
+    pub(crate) fn op_nab(
+        &mut self,
+        _ctx: &Context,
+        mem: &mut MemoryUnit,
+    ) -> Result<OpcodeResult, Alarm> {
+        let a = u64::from(mem.get_a_register());
+        let b = u64::from(mem.get_b_register());
+        let (mut new_a, mut new_b) = (a, b);
+        let mut counts: Vec<u32> = Vec::new();
+        for subword in active_subwords(&self.get_config()) {
+            let w = subword.width;
+            let joined = join_double_length(subword.extract(a), subword.extract(b), w);
+            let (normalized, count) = normalize(joined, 2 * w - 1);
+            let (subword_a, subword_b) = split_double_length(normalized, w);
+            new_a = subword.insert(new_a, subword_a);
+            new_b = subword.insert(new_b, subword_b);
+            counts.push(count);
+        }
+        mem.set_a_register(to_word(new_a));
+        mem.set_b_register(to_word(new_b));
+        self.store_normalize_count(counts.first().copied());
+        Ok(OpcodeResult::default())
+    }
+
+    /// Implements the SCA instruction (Opcode 070).  Each active
+    /// subword of A is scaled (see [`scale`]) left by the number of
+    /// places given by the operand address (scaling right if this is
+    /// negative).  If a left scale loses significant bits, the
+    /// overflow indication for that subword is set.
+    pub(crate) fn op_sca(
+        &mut self,
+        ctx: &Context,
+        mem: &mut MemoryUnit,
+    ) -> Result<OpcodeResult, Alarm> {
+        let count = self.shift_count(ctx, mem)?;
+        let a = u64::from(mem.get_a_register());
+        let mut new_a = a;
+        let mut overflow = OverflowLatch::default();
+        for subword in active_subwords(&self.get_config()) {
+            let (scaled, overflowed) = scale(u128::from(subword.extract(a)), subword.width, count);
+            new_a = subword.insert(
+                new_a,
+                u64::try_from(scaled).expect("subword should fit (this is a bug)"),
+            );
+            if overflowed {
+                overflow.mark(&subword);
+            }
+        }
+        mem.set_a_register(to_word(new_a));
+        self.set_scale_overflow(overflow)?;
+        Ok(OpcodeResult::default())
+    }
+
+    /// Implements the SCB instruction (Opcode 071).  This is the same
+    /// as SCA, but operates on B.
+    pub(crate) fn op_scb(
+        &mut self,
+        ctx: &Context,
+        mem: &mut MemoryUnit,
+    ) -> Result<OpcodeResult, Alarm> {
+        let count = self.shift_count(ctx, mem)?;
+        let b = u64::from(mem.get_b_register());
+        let mut new_b = b;
+        let mut overflow = OverflowLatch::default();
+        for subword in active_subwords(&self.get_config()) {
+            let (scaled, overflowed) = scale(u128::from(subword.extract(b)), subword.width, count);
+            new_b = subword.insert(
+                new_b,
+                u64::try_from(scaled).expect("subword should fit (this is a bug)"),
+            );
+            if overflowed {
+                overflow.mark(&subword);
+            }
+        }
+        mem.set_b_register(to_word(new_b));
+        self.set_scale_overflow(overflow)?;
+        Ok(OpcodeResult::default())
+    }
+
+    /// Implements the SAB instruction (Opcode 072).  Each active
+    /// subword of A is joined with the corresponding subword of B to
+    /// form a double-length value (see [`join_double_length`]), which
+    /// is scaled as for SCA.  The sign bit of the B subword is then
+    /// set from the sign of the result.
+    pub(crate) fn op_sab(
+        &mut self,
+        ctx: &Context,
+        mem: &mut MemoryUnit,
+    ) -> Result<OpcodeResult, Alarm> {
+        let count = self.shift_count(ctx, mem)?;
+        let a = u64::from(mem.get_a_register());
+        let b = u64::from(mem.get_b_register());
+        let (mut new_a, mut new_b) = (a, b);
+        let mut overflow = OverflowLatch::default();
+        for subword in active_subwords(&self.get_config()) {
+            let w = subword.width;
+            let joined = join_double_length(subword.extract(a), subword.extract(b), w);
+            let (scaled, overflowed) = scale(joined, 2 * w - 1, count);
+            let (subword_a, subword_b) = split_double_length(scaled, w);
+            new_a = subword.insert(new_a, subword_a);
+            new_b = subword.insert(new_b, subword_b);
+            if overflowed {
+                overflow.mark(&subword);
+            }
+        }
+        mem.set_a_register(to_word(new_a));
+        mem.set_b_register(to_word(new_b));
+        self.set_scale_overflow(overflow)?;
+        Ok(OpcodeResult::default())
+    }
+
+    /// Set the overflow indication for each subword in which a scale
+    /// instruction lost significant bits (raising OSAL if there are
+    /// any).
+    fn set_scale_overflow(&mut self, overflow: OverflowLatch) -> Result<(), Alarm> {
+        if overflow.is_set() {
+            event!(Level::DEBUG, "scale overflow (overflow {:?})", overflow);
+        }
+        self.record_overflow(overflow)
+    }
+
+    /// Store the shift count of a normalize instruction in the index
+    /// register specified by the instruction.
+    fn store_normalize_count(&mut self, count: Option<u32>) {
+        let j = self.regs.n.index_address();
+        match count {
+            Some(count) if j != 0 => {
+                let count = Signed18Bit::try_from(count)
+                    .expect("normalize shift count should fit into an index register");
+                event!(Level::TRACE, "storing normalize count {count:?} in X{j:o}");
+                self.regs.set_index_register(j, &count);
+            }
+            Some(_) => {
+                // X₀ is always zero.
+            }
+            None => {
+                // No quarter is active, so nothing was shifted.
+                event!(
+                    Level::DEBUG,
+                    "normalize instruction has no active subword; index register unchanged"
+                );
+            }
+        }
+    }
+
+    /// Determine the number of places by which a cycle, normalize or
+    /// scale instruction should shift.  This is the operand address
+    /// (after deferred addressing and indexing), interpreted as a
+    /// signed 17-bit quantity.  Positive counts shift to the left and
+    /// negative counts shift to the right.
+    fn shift_count(&mut self, ctx: &Context, mem: &mut MemoryUnit) -> Result<i32, Alarm> {
+        let address: Address = self.operand_address_with_optional_defer_and_index(ctx, mem)?;
+        let (physical, _mark) = address.split();
+        let value: i32 = i32::try_from(u32::from(physical))
+            .expect("17-bit physical address should fit into i32");
+        let count = if value & 0o200_000 != 0 {
+            value - 0o400_000
+        } else {
+            value
+        };
+        event!(Level::TRACE, "shift count is {count}");
+        Ok(count)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::control::ConfigurationMemorySetup;
+    use super::super::super::{MemoryConfiguration, MemoryUnit};
+    use super::super::tests::{execute_single_instruction, make_ctx};
+    use super::super::{ControlUnit, PanicOnUnmaskedAlarm};
+    use base::prelude::*;
+
+    const COMPLAIN: &str = "failed to set up shift test data";
+
+    /// Execute a shift instruction with configuration `cfg_index`
+    /// (which selects a location in the standard F-memory setup)
+    /// and operand address `address`, indexed by index register 1
+    /// (which contains `x1`).  Returns the resulting values of A and
+    /// B.
+    fn simulate(
+        opcode: Opcode,
+        cfg_index: u8,
+        address: Unsigned18Bit,
+        x1: Signed18Bit,
+        a: Unsigned36Bit,
+        b: Unsigned36Bit,
+    ) -> (Unsigned36Bit, Unsigned36Bit) {
+        let (a, b, _) = simulate_with_overflow(opcode, cfg_index, address, x1, a, b);
+        (a, b)
+    }
+
+    /// As `simulate`, but also returns an indication of whether the
+    /// overflow latch was set.
+    fn simulate_with_overflow(
+        opcode: Opcode,
+        cfg_index: u8,
+        address: Unsigned18Bit,
+        x1: Signed18Bit,
+        a: Unsigned36Bit,
+        b: Unsigned36Bit,
+    ) -> (Unsigned36Bit, Unsigned36Bit, bool) {
+        let ctx = make_ctx();
+        let mut control = ControlUnit::new(
+            PanicOnUnmaskedAlarm::Yes,
+            ConfigurationMemorySetup::StandardForTestingOnly,
+        );
+        let mut mem = MemoryUnit::new(
+            &ctx,
+            &MemoryConfiguration {
+                with_u_memory: false,
+            },
+        );
+        mem.set_a_register(a);
+        mem.set_b_register(b);
+        control.regs.set_index_register(Unsigned6Bit::ONE, &x1);
+        let inst = SymbolicInstruction {
+            held: false,
+            configuration: Unsigned5Bit::try_from(cfg_index).expect(COMPLAIN),
+            opcode,
+            index: Unsigned6Bit::ONE,
+            operand_address: OperandAddress::Direct(Address::from(address)),
+        };
+        let result = execute_single_instruction(&ctx, &mut control, &mut mem, &inst);
+        if let Err(e) = result {
+            panic!("{:?} instruction failed: {}", opcode, e);
+        }
+        (
+            mem.get_a_register(),
+            mem.get_b_register(),
+            control.regs.overflow.is_set(),
+        )
+    }
+
+    #[test]
+    fn test_cycle_table() {
+        struct Case {
+            opcode: Opcode,
+            cfg_index: u8,
+            address: Unsigned18Bit,
+            x1: Signed18Bit,
+            a: Unsigned36Bit,
+            b: Unsigned36Bit,
+            expected_a: Unsigned36Bit,
+            expected_b: Unsigned36Bit,
+        }
+        let minus_three = Signed18Bit::from(-3_i8);
+        let cases: &[Case] = &[
+            // Zero count: no change.
+            Case {
+                opcode: Opcode::Cya,
+                cfg_index: 0,
+                address: u18!(0),
+                x1: Signed18Bit::ZERO,
+                a: u36!(0o123_456_701_234),
+                b: u36!(0o555_555_555_555),
+                expected_a: u36!(0o123_456_701_234),
+                expected_b: u36!(0o555_555_555_555),
+            },
+            // Cycle left by 3 (one octal digit).
+            Case {
+                opcode: Opcode::Cya,
+                cfg_index: 0,
+                address: u18!(3),
+                x1: Signed18Bit::ZERO,
+                a: u36!(0o123_456_701_234),
+                b: u36!(0o555_555_555_555),
+                expected_a: u36!(0o234_567_012_341),
+                expected_b: u36!(0o555_555_555_555),
+            },
+            // Cycle right by 3, negative count from the index register.
+            Case {
+                opcode: Opcode::Cya,
+                cfg_index: 0,
+                address: u18!(0),
+                x1: minus_three,
+                a: u36!(0o123_456_701_234),
+                b: u36!(0),
+                expected_a: u36!(0o412_345_670_123),
+                expected_b: u36!(0),
+            },
+            // Cycle right by 3, negative count in the address itself.
+            Case {
+                opcode: Opcode::Cyb,
+                cfg_index: 0,
+                address: u18!(0o377_775),
+                x1: Signed18Bit::ZERO,
+                a: u36!(0),
+                b: u36!(0o123_456_701_234),
+                expected_a: u36!(0),
+                expected_b: u36!(0o412_345_670_123),
+            },
+            // Cycling by the word length is a no-op.
+            Case {
+                opcode: Opcode::Cyb,
+                cfg_index: 0,
+                address: u18!(36),
+                x1: Signed18Bit::ZERO,
+                a: u36!(0),
+                b: u36!(0o123_456_701_234),
+                expected_a: u36!(0),
+                expected_b: u36!(0o123_456_701_234),
+            },
+            // F-memory location 16 is configuration 200 (both halves);
+            // each half cycles separately.
+            Case {
+                opcode: Opcode::Cya,
+                cfg_index: 16,
+                address: u18!(3),
+                x1: Signed18Bit::ZERO,
+                a: u36!(0o123_456_701_234),
+                b: u36!(0),
+                expected_a: u36!(0o234_561_012_347),
+                expected_b: u36!(0),
+            },
+            // F-memory location 24 is configuration 600 (quarters);
+            // cycle right by 3.
+            Case {
+                opcode: Opcode::Cya,
+                cfg_index: 24,
+                address: u18!(0),
+                x1: minus_three,
+                a: u36!(0o123_456_701_234),
+                b: u36!(0),
+                expected_a: u36!(0o312_645_170_423),
+                expected_b: u36!(0),
+            },
+            // F-memory location 1 is configuration 340 (right half
+            // only); the left half is unchanged.
+            Case {
+                opcode: Opcode::Cyb,
+                cfg_index: 1,
+                address: u18!(6),
+                x1: Signed18Bit::ZERO,
+                a: u36!(0),
+                b: u36!(0o123_456_701_234),
+                expected_a: u36!(0),
+                expected_b: u36!(0o123_456_123_470),
+            },
+            // CAB cycles A and B together, with A on the left.
+            Case {
+                opcode: Opcode::Cab,
+                cfg_index: 0,
+                address: u18!(3),
+                x1: Signed18Bit::ZERO,
+                a: u36!(0o123_456_701_234),
+                b: u36!(0o765_432_107_654),
+                expected_a: u36!(0o234_567_012_347),
+                expected_b: u36!(0o654_321_076_541),
+            },
+            Case {
+                opcode: Opcode::Cab,
+                cfg_index: 0,
+                address: u18!(0),
+                x1: minus_three,
+                a: u36!(0o123_456_701_234),
+                b: u36!(0o765_432_107_654),
+                expected_a: u36!(0o412_345_670_123),
+                expected_b: u36!(0o476_543_210_765),
+            },
+            Case {
+                opcode: Opcode::Cab,
+                cfg_index: 0,
+                address: u18!(0),
+                x1: Signed18Bit::ZERO,
+                a: u36!(0o123_456_701_234),
+                b: u36!(0o765_432_107_654),
+                expected_a: u36!(0o123_456_701_234),
+                expected_b: u36!(0o765_432_107_654),
+            },
+            // CAB in halves: the left halves of A and B form one 36-bit
+            // quantity and the right halves another.
+            Case {
+                opcode: Opcode::Cab,
+                cfg_index: 16,
+                address: u18!(3),
+                x1: Signed18Bit::ZERO,
+                a: u36!(0o123_456_701_234),
+                b: u36!(0o765_432_107_654),
+                expected_a: u36!(0o234_567_012_341),
+                expected_b: u36!(0o654_321_076_547),
+            },
+        ];
+        for case in cases {
+            let (a, b) = simulate(
+                case.opcode,
+                case.cfg_index,
+                case.address,
+                case.x1,
+                case.a,
+                case.b,
+            );
+            assert_eq!(
+                (a, b),
+                (case.expected_a, case.expected_b),
+                "{:?} with configuration index {}, count {:o}+{:?}, A={:o}, B={:o}: expected A={:o}, B={:o}, got A={:o}, B={:o}",
+                case.opcode,
+                case.cfg_index,
+                case.address,
+                case.x1,
+                case.a,
+                case.b,
+                case.expected_a,
+                case.expected_b,
+                a,
+                b
+            );
+        }
+    }
+
+    /// Execute a normalize instruction with configuration
+    /// `cfg_index`, which stores its count in index register `j`.
+    /// Returns the resulting values of A, B and Xⱼ.
+    fn simulate_normalize(
+        opcode: Opcode,
+        cfg_index: u8,
+        j: Unsigned6Bit,
+        a: Unsigned36Bit,
+        b: Unsigned36Bit,
+    ) -> (Unsigned36Bit, Unsigned36Bit, Signed18Bit) {
+        let ctx = make_ctx();
+        let mut control = ControlUnit::new(
+            PanicOnUnmaskedAlarm::Yes,
+            ConfigurationMemorySetup::StandardForTestingOnly,
+        );
+        let mut mem = MemoryUnit::new(
+            &ctx,
+            &MemoryConfiguration {
+                with_u_memory: false,
+            },
+        );
+        mem.set_a_register(a);
+        mem.set_b_register(b);
+        if j != 0 {
+            // Make sure the count really is written.
+            control
+                .regs
+                .set_index_register(j, &Signed18Bit::from(0o123_i8));
+        }
+        let inst = SymbolicInstruction {
+            held: false,
+            configuration: Unsigned5Bit::try_from(cfg_index).expect(COMPLAIN),
+            opcode,
+            index: j,
+            operand_address: OperandAddress::Direct(Address::ZERO),
+        };
+        let result = execute_single_instruction(&ctx, &mut control, &mut mem, &inst);
+        if let Err(e) = result {
+            panic!("{:?} instruction failed: {}", opcode, e);
+        }
+        (
+            mem.get_a_register(),
+            mem.get_b_register(),
+            control.regs.get_index_register(j),
+        )
+    }
+
+    #[test]
+    fn test_normalize_table() {
+        struct Case {
+            opcode: Opcode,
+            cfg_index: u8,
+            a: Unsigned36Bit,
+            b: Unsigned36Bit,
+            expected_a: Unsigned36Bit,
+            expected_b: Unsigned36Bit,
+            expected_count: i8,
+        }
+        let cases: &[Case] = &[
+            Case {
+                opcode: Opcode::Noa,
+                cfg_index: 0,
+                a: u36!(1),
+                b: u36!(0),
+                expected_a: u36!(0o200_000_000_000),
+                expected_b: u36!(0),
+                expected_count: 34,
+            },
+            Case {
+                opcode: Opcode::Noa,
+                cfg_index: 0,
+                a: u36!(0o017_777_777_777),
+                b: u36!(0o123),
+                expected_a: u36!(0o377_777_777_760),
+                expected_b: u36!(0o123),
+                expected_count: 4,
+            },
+            // Negative values are normalized by shifting in ones.
+            Case {
+                opcode: Opcode::Noa,
+                cfg_index: 0,
+                a: u36!(0o777_777_777_776),
+                b: u36!(0),
+                expected_a: u36!(0o577_777_777_777),
+                expected_b: u36!(0),
+                expected_count: 34,
+            },
+            // Values which are already normalized are unchanged.
+            Case {
+                opcode: Opcode::Noa,
+                cfg_index: 0,
+                a: u36!(0o200_000_000_000),
+                b: u36!(0),
+                expected_a: u36!(0o200_000_000_000),
+                expected_b: u36!(0),
+                expected_count: 0,
+            },
+            Case {
+                opcode: Opcode::Noa,
+                cfg_index: 0,
+                a: u36!(0o500_000_000_000),
+                b: u36!(0),
+                expected_a: u36!(0o500_000_000_000),
+                expected_b: u36!(0),
+                expected_count: 0,
+            },
+            // Zero and minus zero cannot be normalized.
+            Case {
+                opcode: Opcode::Noa,
+                cfg_index: 0,
+                a: u36!(0),
+                b: u36!(0),
+                expected_a: u36!(0),
+                expected_b: u36!(0),
+                expected_count: 0,
+            },
+            Case {
+                opcode: Opcode::Noa,
+                cfg_index: 0,
+                a: u36!(0o777_777_777_777),
+                b: u36!(0),
+                expected_a: u36!(0o777_777_777_777),
+                expected_b: u36!(0),
+                expected_count: 0,
+            },
+            // F-memory location 16 is configuration 200 (both
+            // halves).  The count is that of the left half.
+            Case {
+                opcode: Opcode::Noa,
+                cfg_index: 16,
+                a: u36!(0o000_001_000_004),
+                b: u36!(0),
+                expected_a: u36!(0o200_000_200_000),
+                expected_b: u36!(0),
+                expected_count: 16,
+            },
+            // NAB normalizes AB as a double-length value, whose
+            // sign is the sign bit of A and whose 70-bit magnitude is
+            // the rest of A followed by the rest of B.
+            Case {
+                opcode: Opcode::Nab,
+                cfg_index: 0,
+                a: u36!(0),
+                b: u36!(1),
+                expected_a: u36!(0o200_000_000_000),
+                expected_b: u36!(0),
+                expected_count: 69,
+            },
+            // The sign bit of B is not part of the value, and is set
+            // from the sign of the result.
+            Case {
+                opcode: Opcode::Nab,
+                cfg_index: 0,
+                a: u36!(0),
+                b: u36!(0o400_000_000_003),
+                expected_a: u36!(0o300_000_000_000),
+                expected_b: u36!(0),
+                expected_count: 68,
+            },
+            Case {
+                opcode: Opcode::Nab,
+                cfg_index: 0,
+                a: u36!(0o000_000_000_001),
+                b: u36!(0o200_000_000_000),
+                expected_a: u36!(0o300_000_000_000),
+                expected_b: u36!(0),
+                expected_count: 34,
+            },
+            Case {
+                opcode: Opcode::Nab,
+                cfg_index: 0,
+                a: u36!(0o777_777_777_777),
+                b: u36!(0o777_777_777_776),
+                expected_a: u36!(0o577_777_777_777),
+                expected_b: u36!(0o777_777_777_777),
+                expected_count: 69,
+            },
+            Case {
+                opcode: Opcode::Nab,
+                cfg_index: 0,
+                a: u36!(0o777_777_777_776),
+                b: u36!(0o377_777_777_777),
+                expected_a: u36!(0o577_777_777_777),
+                expected_b: u36!(0o777_777_777_777),
+                expected_count: 34,
+            },
+            // F-memory location 16 is configuration 200 (both
+            // halves).  The count is that of the left half.
+            Case {
+                opcode: Opcode::Nab,
+                cfg_index: 16,
+                a: u36!(0),
+                b: u36!(0o000_001_400_001),
+                expected_a: u36!(0o200_000_200_000),
+                expected_b: u36!(0),
+                expected_count: 33,
+            },
+            Case {
+                opcode: Opcode::Nab,
+                cfg_index: 0,
+                a: u36!(0o777_777_777_777),
+                b: u36!(0o777_777_777_777),
+                expected_a: u36!(0o777_777_777_777),
+                expected_b: u36!(0o777_777_777_777),
+                expected_count: 0,
+            },
+        ];
+        for case in cases {
+            let (a, b, count) =
+                simulate_normalize(case.opcode, case.cfg_index, u6!(2), case.a, case.b);
+            assert_eq!(
+                (a, b, count),
+                (
+                    case.expected_a,
+                    case.expected_b,
+                    Signed18Bit::from(case.expected_count)
+                ),
+                "{:?} with configuration index {}, A={:o}, B={:o}",
+                case.opcode,
+                case.cfg_index,
+                case.a,
+                case.b,
+            );
+        }
+    }
+
+    #[test]
+    fn test_normalize_x0_unchanged() {
+        // X₀ is always zero, so the count is simply discarded.
+        assert_eq!(
+            simulate_normalize(Opcode::Noa, 0, Unsigned6Bit::ZERO, u36!(1), u36!(0)),
+            (u36!(0o200_000_000_000), u36!(0), Signed18Bit::ZERO)
+        );
+    }
+
+    #[test]
+    fn test_scale_table() {
+        struct Case {
+            opcode: Opcode,
+            cfg_index: u8,
+            address: Unsigned18Bit,
+            x1: Signed18Bit,
+            a: Unsigned36Bit,
+            b: Unsigned36Bit,
+            expected_a: Unsigned36Bit,
+            expected_b: Unsigned36Bit,
+            expected_overflow: bool,
+        }
+        let minus_one = Signed18Bit::from(-1_i8);
+        let minus_three = Signed18Bit::from(-3_i8);
+        let cases: &[Case] = &[
+            // Scale left by 3 (one octal digit).
+            Case {
+                opcode: Opcode::Sca,
+                cfg_index: 0,
+                address: u18!(3),
+                x1: Signed18Bit::ZERO,
+                a: u36!(0o001_234_567_012),
+                b: u36!(0o555),
+                expected_a: u36!(0o012_345_670_120),
+                expected_b: u36!(0o555),
+                expected_overflow: false,
+            },
+            // Scale right by 3; the sign bit is copied in.
+            Case {
+                opcode: Opcode::Sca,
+                cfg_index: 0,
+                address: u18!(0),
+                x1: minus_three,
+                a: u36!(0o123_456_701_234),
+                b: u36!(0),
+                expected_a: u36!(0o012_345_670_123),
+                expected_b: u36!(0),
+                expected_overflow: false,
+            },
+            Case {
+                opcode: Opcode::Sca,
+                cfg_index: 0,
+                address: u18!(0),
+                x1: minus_three,
+                a: u36!(0o765_432_107_654),
+                b: u36!(0),
+                expected_a: u36!(0o776_543_210_765),
+                expected_b: u36!(0),
+                expected_overflow: false,
+            },
+            // Scaling a negative value left shifts in ones.
+            Case {
+                opcode: Opcode::Sca,
+                cfg_index: 0,
+                address: u18!(3),
+                x1: Signed18Bit::ZERO,
+                a: u36!(0o776_543_210_765),
+                b: u36!(0),
+                expected_a: u36!(0o765_432_107_657),
+                expected_b: u36!(0),
+                expected_overflow: false,
+            },
+            // Scaling left loses a significant bit.
+            Case {
+                opcode: Opcode::Sca,
+                cfg_index: 0,
+                address: u18!(1),
+                x1: Signed18Bit::ZERO,
+                a: u36!(0o200_000_000_000),
+                b: u36!(0),
+                expected_a: u36!(0),
+                expected_b: u36!(0),
+                expected_overflow: true,
+            },
+            Case {
+                opcode: Opcode::Sca,
+                cfg_index: 0,
+                address: u18!(1),
+                x1: Signed18Bit::ZERO,
+                a: u36!(0o577_777_777_777),
+                b: u36!(0),
+                expected_a: u36!(0o777_777_777_777),
+                expected_b: u36!(0),
+                expected_overflow: true,
+            },
+            // Very large counts leave only copies of the sign bit.
+            Case {
+                opcode: Opcode::Sca,
+                cfg_index: 0,
+                address: u18!(100),
+                x1: Signed18Bit::ZERO,
+                a: u36!(1),
+                b: u36!(0),
+                expected_a: u36!(0),
+                expected_b: u36!(0),
+                expected_overflow: true,
+            },
+            // Minus zero is unchanged by scaling.
+            Case {
+                opcode: Opcode::Sca,
+                cfg_index: 0,
+                address: u18!(100),
+                x1: Signed18Bit::ZERO,
+                a: u36!(0o777_777_777_777),
+                b: u36!(0),
+                expected_a: u36!(0o777_777_777_777),
+                expected_b: u36!(0),
+                expected_overflow: false,
+            },
+            Case {
+                opcode: Opcode::Sca,
+                cfg_index: 0,
+                address: u18!(0o377_775),
+                x1: Signed18Bit::ZERO,
+                a: u36!(0o777_777_777_777),
+                b: u36!(0),
+                expected_a: u36!(0o777_777_777_777),
+                expected_b: u36!(0),
+                expected_overflow: false,
+            },
+            // SCB operates on B only.
+            Case {
+                opcode: Opcode::Scb,
+                cfg_index: 0,
+                address: u18!(2),
+                x1: Signed18Bit::ZERO,
+                a: u36!(0o123),
+                b: u36!(0o000_000_000_005),
+                expected_a: u36!(0o123),
+                expected_b: u36!(0o000_000_000_024),
+                expected_overflow: false,
+            },
+            // SAB scales the double-length value AB, whose sign is
+            // the sign bit of A and whose 70-bit magnitude is the
+            // rest of A followed by the rest of B.
+            Case {
+                opcode: Opcode::Sab,
+                cfg_index: 0,
+                address: u18!(1),
+                x1: Signed18Bit::ZERO,
+                a: u36!(0),
+                b: u36!(0o200_000_000_000),
+                expected_a: u36!(1),
+                expected_b: u36!(0),
+                expected_overflow: false,
+            },
+            Case {
+                opcode: Opcode::Sab,
+                cfg_index: 0,
+                address: u18!(0),
+                x1: minus_one,
+                a: u36!(1),
+                b: u36!(0o377_777_777_776),
+                expected_a: u36!(0),
+                expected_b: u36!(0o377_777_777_777),
+                expected_overflow: false,
+            },
+            // The sign bit of B is not part of the value, and is set
+            // from the sign of the result.
+            Case {
+                opcode: Opcode::Sab,
+                cfg_index: 0,
+                address: u18!(1),
+                x1: Signed18Bit::ZERO,
+                a: u36!(0),
+                b: u36!(0o400_000_000_000),
+                expected_a: u36!(0),
+                expected_b: u36!(0),
+                expected_overflow: false,
+            },
+            Case {
+                opcode: Opcode::Sab,
+                cfg_index: 0,
+                address: u18!(0),
+                x1: minus_three,
+                a: u36!(0o777_777_777_777),
+                b: u36!(0),
+                expected_a: u36!(0o777_777_777_777),
+                expected_b: u36!(0o740_000_000_000),
+                expected_overflow: false,
+            },
+            Case {
+                opcode: Opcode::Sab,
+                cfg_index: 0,
+                address: u18!(1),
+                x1: Signed18Bit::ZERO,
+                a: u36!(0o200_000_000_000),
+                b: u36!(0),
+                expected_a: u36!(0),
+                expected_b: u36!(0),
+                expected_overflow: true,
+            },
+            // F-memory location 16 is configuration 200 (both
+            // halves).  Each half of B shifts into the same half of A.
+            Case {
+                opcode: Opcode::Sab,
+                cfg_index: 16,
+                address: u18!(1),
+                x1: Signed18Bit::ZERO,
+                a: u36!(0),
+                b: u36!(0o200_000_200_000),
+                expected_a: u36!(0o000_001_000_001),
+                expected_b: u36!(0),
+                expected_overflow: false,
+            },
+            // F-memory location 16 is configuration 200 (both halves).  Only
+            // the left half overflows.
+            Case {
+                opcode: Opcode::Sca,
+                cfg_index: 16,
+                address: u18!(1),
+                x1: Signed18Bit::ZERO,
+                a: u36!(0o200_000_000_001),
+                b: u36!(0),
+                expected_a: u36!(0o000_000_000_002),
+                expected_b: u36!(0),
+                expected_overflow: true,
+            },
+            // F-memory location 1 is configuration 340 (right half only).
+            Case {
+                opcode: Opcode::Sca,
+                cfg_index: 1,
+                address: u18!(0),
+                x1: minus_one,
+                a: u36!(0o400_000_400_000),
+                b: u36!(0),
+                expected_a: u36!(0o400_000_600_000),
+                expected_b: u36!(0),
+                expected_overflow: false,
+            },
+        ];
+        for case in cases {
+            let result = simulate_with_overflow(
+                case.opcode,
+                case.cfg_index,
+                case.address,
+                case.x1,
+                case.a,
+                case.b,
+            );
+            assert_eq!(
+                result,
+                (case.expected_a, case.expected_b, case.expected_overflow),
+                "{:?} with configuration index {}, address {:o}, X1={:?}, A={:o}, B={:o}",
+                case.opcode,
+                case.cfg_index,
+                case.address,
+                case.x1,
+                case.a,
+                case.b,
+            );
+        }
+    }
+}
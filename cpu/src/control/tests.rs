@@ -1,14 +1,18 @@
 use core::time::Duration;
 
-use super::alarm::{Alarm, AlarmDetails};
+use super::alarm::{Alarm, AlarmDetails, AlarmKind};
 use super::context::Context;
 use super::control::ConfigurationMemorySetup;
+use super::io::Unit;
 use super::memory::MetaBitChange;
 use super::DeviceManager;
-use super::{ControlUnit, MemoryConfiguration, MemoryUnit, PanicOnUnmaskedAlarm, UpdateE};
+use super::{
+    execute_opcode, ControlUnit, MemoryConfiguration, MemoryUnit, OpcodeResult,
+    PanicOnUnmaskedAlarm, UpdateE,
+};
 use base::prelude::*;
 
-fn make_ctx() -> Context {
+pub(super) fn make_ctx() -> Context {
     Context {
         simulated_time: Duration::new(42, 42),
         real_elapsed_time: Duration::new(7, 12),
@@ -33,10 +37,29 @@ fn setup(ctx: &Context, p: Address) -> (ControlUnit, MemoryUnit) {
     (control, mem)
 }
 
+/// Execute the instruction `inst` on its own, as if it had just been
+/// fetched into the N register.  This allows the tests for individual
+/// instructions to check their effects without setting up a program
+/// for the control unit to run.
+pub(super) fn execute_single_instruction(
+    ctx: &Context,
+    control: &mut ControlUnit,
+    mem: &mut MemoryUnit,
+    inst: &SymbolicInstruction,
+) -> Result<OpcodeResult, Alarm> {
+    control
+        .update_n_register(Instruction::from(inst).bits())
+        .expect("failed to set up instruction as test data");
+    let mut devices = DeviceManager::default();
+    let p = control.regs.p;
+    execute_opcode(ctx, p, &inst.opcode(), control, &mut devices, mem)
+}
+
 #[test]
 fn test_roundtuital_not_maskable() {
     // Simulate an unumplemented instruction, verify that the alarm
     // unit states that an unmaskable alarm (ROUNDTUITAL) is active.
+    // SKX configuration 2 is not implemented yet.
     const COMPLAIN: &str = "failed to set up instruction as test data";
     let context = make_ctx();
     let p = Address::from(u18!(0o250));
@@ -44,8 +67,8 @@ fn test_roundtuital_not_maskable() {
     let (mut control, mut mem) = setup(&context, p);
     let sym = SymbolicInstruction {
         held: false,
-        configuration: Unsigned5Bit::ZERO,
-        opcode: Opcode::Sca,
+        configuration: u5!(2),
+        opcode: Opcode::Skx,
         index: u6!(1),
         operand_address: OperandAddress::Direct(Address::ZERO),
     };
@@ -67,7 +90,7 @@ fn test_roundtuital_not_maskable() {
         control.execute_instruction(&context, &mut devices, &mut mem, &mut poll_order_change);
     match result {
         Ok(_) => {
-            panic!("execution of SCA is not expected to succeed, it is not implemented yet: {result:?}");
+            panic!("execution of ²SKX is not expected to succeed, it is not implemented yet: {result:?}");
         }
         Err((
             Alarm {
@@ -77,8 +100,502 @@ fn test_roundtuital_not_maskable() {
             _,
         )) => (),
         Err(_) => {
-            panic!("expected execution of SCA to raise ROUNDTUITAL, but got {result:?}");
+            panic!("expected execution of ²SKX to raise ROUNDTUITAL, but got {result:?}");
         }
     }
     assert!(control.unmasked_alarm_active());
 }
+
+/// Execute an instruction with opcode `opcode` in sequence 41, then
+/// (as if sequence 41 had been dismissed) raise only the flag of
+/// sequence 40, and return the sequence which will run next.
+fn sequence_after(opcode: Opcode, held: bool) -> Option<SequenceNumber> {
+    const COMPLAIN: &str = "failed to set up instruction as test data";
+    let context = make_ctx();
+    let p = Address::from(u18!(0o250));
+    let mut devices = DeviceManager::default();
+    let (mut control, mut mem) = setup(&context, p);
+    control.regs.k = Some(u6!(0o41));
+    control.regs.flags.lower_all();
+    control.regs.flags.raise(&u6!(0o41));
+    let sym = SymbolicInstruction {
+        held,
+        configuration: Unsigned5Bit::ZERO,
+        opcode,
+        index: Unsigned6Bit::ZERO,
+        operand_address: OperandAddress::Direct(Address::from(u18!(0o100))),
+    };
+    let instruction_bits = Instruction::from(&sym).bits();
+    control
+        .memory_store_without_exchange(
+            &context,
+            &mut mem,
+            &p,
+            &instruction_bits,
+            &UpdateE::No,
+            &MetaBitChange::None,
+        )
+        .expect(COMPLAIN);
+    let mut poll_order_change: Option<SequenceNumber> = None;
+    if let Err((alarm, _)) =
+        control.execute_instruction(&context, &mut devices, &mut mem, &mut poll_order_change)
+    {
+        panic!("execution of {opcode:?} failed: {alarm}");
+    }
+    assert_eq!(control.regs.k, Some(u6!(0o41)));
+    control.regs.flags.lower(&u6!(0o41));
+    control.regs.flags.raise(&u6!(0o40));
+    control.select_sequence(&mut mem);
+    control.regs.k
+}
+
+#[test]
+fn test_held_instruction_prevents_sequence_change() {
+    // ITE is normally held (the assembler sets the hold bit), so no
+    // sequence change happens before the next instruction.
+    assert!(Opcode::Ite.hold_is_implicit());
+    assert_eq!(sequence_after(Opcode::Ite, true), Some(u6!(0o41)));
+    // If the hold bit is not set, the sequence change takes place.
+    assert_eq!(sequence_after(Opcode::Ite, false), Some(u6!(0o40)));
+}
+
+#[test]
+fn test_hold_bit_applies_to_any_instruction() {
+    // LDA is not normally held, but the hold bit has the same effect
+    // when it is set.
+    assert!(!Opcode::Lda.hold_is_implicit());
+    assert_eq!(sequence_after(Opcode::Lda, true), Some(u6!(0o41)));
+    assert_eq!(sequence_after(Opcode::Lda, false), Some(u6!(0o40)));
+}
+
+#[test]
+fn test_failed_instruction_does_not_change_hold() {
+    // An instruction which raises an alarm has not completed, so the
+    // hold bit of the previous instruction is still the one which
+    // counts.  ²SKX is not implemented, so it raises ROUNDTUITAL.
+    const COMPLAIN: &str = "failed to set up instruction as test data";
+    let context = make_ctx();
+    let p = Address::from(u18!(0o250));
+    let mut devices = DeviceManager::default();
+    let (mut control, mut mem) = setup(&context, p);
+    let sym = SymbolicInstruction {
+        held: false,
+        configuration: u5!(2),
+        opcode: Opcode::Skx,
+        index: u6!(1),
+        operand_address: OperandAddress::Direct(Address::ZERO),
+    };
+    control
+        .memory_store_without_exchange(
+            &context,
+            &mut mem,
+            &p,
+            &Instruction::from(&sym).bits(),
+            &UpdateE::No,
+            &MetaBitChange::None,
+        )
+        .expect(COMPLAIN);
+    control.regs.prev_hold = true;
+    let mut poll_order_change: Option<SequenceNumber> = None;
+    assert!(control
+        .execute_instruction(&context, &mut devices, &mut mem, &mut poll_order_change)
+        .is_err());
+    assert!(control.regs.prev_hold);
+}
+
+/// Execute an XEQ instruction at 0o250 whose operand is the
+/// instruction `target` (stored at 0o300), returning the control
+/// unit and any alarm raised by executing the XEQ instruction.
+fn simulate_xeq(
+    xeq_enabled: bool,
+    target: &SymbolicInstruction,
+) -> (ControlUnit, Result<(), Alarm>) {
+    const COMPLAIN: &str = "failed to set up instruction as test data";
+    let context = make_ctx();
+    let p = Address::from(u18!(0o250));
+    let target_address = Address::from(u18!(0o300));
+    let mut devices = DeviceManager::default();
+    let (mut control, mut mem) = setup(&context, p);
+    control.set_xeq_enabled(xeq_enabled);
+    let xeq = SymbolicInstruction {
+        held: false,
+        configuration: Unsigned5Bit::ZERO,
+        opcode: Opcode::Xeq,
+        index: Unsigned6Bit::ZERO,
+        operand_address: OperandAddress::Direct(target_address),
+    };
+    for (address, inst) in [(p, &xeq), (target_address, target)] {
+        control
+            .memory_store_without_exchange(
+                &context,
+                &mut mem,
+                &address,
+                &Instruction::from(inst).bits(),
+                &UpdateE::No,
+                &MetaBitChange::None,
+            )
+            .expect(COMPLAIN);
+    }
+    let mut poll_order_change: Option<SequenceNumber> = None;
+    let result = control
+        .execute_instruction(&context, &mut devices, &mut mem, &mut poll_order_change)
+        .map(|_| ())
+        .map_err(|(alarm, _address)| alarm);
+    (control, result)
+}
+
+fn skx_instruction() -> SymbolicInstruction {
+    // ⁰SKX sets X₁ to the operand value.
+    SymbolicInstruction {
+        held: false,
+        configuration: Unsigned5Bit::ZERO,
+        opcode: Opcode::Skx,
+        index: u6!(1),
+        operand_address: OperandAddress::Direct(Address::from(u18!(0o1234))),
+    }
+}
+
+#[test]
+fn test_xeq_is_invalid_by_default() {
+    let (control, result) = simulate_xeq(false, &skx_instruction());
+    match result {
+        Err(Alarm {
+            sequence: _,
+            details: AlarmDetails::OCSAL(_, _),
+        }) => (),
+        _ => {
+            panic!(
+                "expected execution of XEQ to raise OCSAL when XEQ is disabled, but got {result:?}"
+            );
+        }
+    }
+    assert_eq!(control.regs.get_index_register(u6!(1)), 0);
+}
+
+#[test]
+fn test_xeq_executes_operand_without_changing_p() {
+    let (control, result) = simulate_xeq(true, &skx_instruction());
+    if let Err(alarm) = result {
+        panic!("execution of XEQ failed: {alarm}");
+    }
+    assert_eq!(
+        control.regs.get_index_register(u6!(1)),
+        u18!(0o1234).reinterpret_as_signed()
+    );
+    // P has advanced past the XEQ instruction only.
+    assert_eq!(control.regs.p, Address::from(u18!(0o251)));
+}
+
+#[test]
+fn test_xeq_of_jump() {
+    let jmp = SymbolicInstruction {
+        held: false,
+        configuration: Unsigned5Bit::ZERO,
+        opcode: Opcode::Jmp,
+        index: Unsigned6Bit::ZERO,
+        operand_address: OperandAddress::Direct(Address::from(u18!(0o400))),
+    };
+    let (control, result) = simulate_xeq(true, &jmp);
+    if let Err(alarm) = result {
+        panic!("execution of XEQ failed: {alarm}");
+    }
+    assert_eq!(control.regs.p, Address::from(u18!(0o400)));
+}
+
+fn ios_instruction(unit: Unsigned6Bit, operand: u32) -> Unsigned36Bit {
+    Instruction::from(&SymbolicInstruction {
+        held: false,
+        configuration: Unsigned5Bit::ZERO,
+        opcode: Opcode::Ios,
+        index: unit,
+        operand_address: OperandAddress::Direct(Address::from(
+            Unsigned18Bit::try_from(operand).expect("IOS operand should fit in 18 bits"),
+        )),
+    })
+    .bits()
+}
+
+/// Connect unit 41 with mode 1 (route IOSAL to the program), then
+/// issue an IOS with an invalid command for unit 52, with IOSAL
+/// masked or not according to `iosal_masked`.  Neither instruction
+/// should stop the machine.
+fn route_iosal_and_issue_bad_ios(
+    iosal_masked: bool,
+) -> (Context, Address, ControlUnit, MemoryUnit, DeviceManager) {
+    const COMPLAIN: &str = "failed to set up instruction as test data";
+    let context = make_ctx();
+    let p = Address::from(u18!(0o250));
+    let mut devices = DeviceManager::default();
+    let (mut control, mut mem) = setup(&context, p);
+    control
+        .set_alarm_masked(AlarmKind::IOSAL, iosal_masked)
+        .expect(COMPLAIN);
+    for (offset, word) in [
+        ios_instruction(u6!(0o41), 0o30_001),
+        ios_instruction(u6!(0o52), 0o70_000),
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        control
+            .memory_store_without_exchange(
+                &context,
+                &mut mem,
+                &p.index_by(Signed18Bit::try_from(offset as i32).expect(COMPLAIN)),
+                &word,
+                &UpdateE::No,
+                &MetaBitChange::None,
+            )
+            .expect(COMPLAIN);
+    }
+    for _ in 0..2 {
+        let mut poll_order_change: Option<SequenceNumber> = None;
+        if let Err((alarm, _)) =
+            control.execute_instruction(&context, &mut devices, &mut mem, &mut poll_order_change)
+        {
+            panic!("IOSAL should have been routed to sequence 41, but execution stopped: {alarm}");
+        }
+    }
+    (context, p, control, mem, devices)
+}
+
+#[test]
+fn test_iosal_routed_to_sequence_41() {
+    const COMPLAIN: &str = "failed to set up instruction as test data";
+    let (context, p, mut control, mut mem, mut devices) = route_iosal_and_issue_bad_ios(false);
+    assert!(!control.unmasked_alarm_active());
+    assert!(control.regs.flags.current_flag_state(&u6!(0o41)));
+    // The special field of the unit 41 report word identifies the
+    // alarm (code 1 is IOSAL) and the affected unit (52).
+    let report = devices
+        .report(
+            &context,
+            control.regs.k,
+            u6!(0o41),
+            true,
+            &mut control.alarm_unit,
+        )
+        .expect("unit 41 should produce a report word");
+    assert_eq!(report, u36!(0o015_241_440_001));
+
+    // Once unit 41 is disconnected, IOSAL stops the machine again.
+    control
+        .alarm_unit
+        .set_program_alarm_mode(Unsigned12Bit::ZERO);
+    control.regs.k = Some(Unsigned6Bit::ZERO);
+    control.regs.flags.lower_all();
+    control.regs.flags.raise(&SequenceNumber::ZERO);
+    control.regs.p = p.index_by(Signed18Bit::try_from(1).expect(COMPLAIN));
+    let mut poll_order_change: Option<SequenceNumber> = None;
+    assert!(matches!(
+        control.execute_instruction(&context, &mut devices, &mut mem, &mut poll_order_change),
+        Err((
+            Alarm {
+                details: AlarmDetails::IOSAL { .. },
+                ..
+            },
+            _
+        ))
+    ));
+}
+
+#[test]
+fn test_masked_iosal_is_still_routed_to_sequence_41() {
+    // Routing depends on the connect mode of unit 41, not on the
+    // alarm mask.
+    let (context, _, mut control, _, mut devices) = route_iosal_and_issue_bad_ios(true);
+    assert!(control.regs.flags.current_flag_state(&u6!(0o41)));
+    let report = devices
+        .report(
+            &context,
+            control.regs.k,
+            u6!(0o41),
+            true,
+            &mut control.alarm_unit,
+        )
+        .expect("unit 41 should produce a report word");
+    assert_eq!(report, u36!(0o015_241_440_001));
+}
+
+#[test]
+fn test_masked_misal_from_device_poll_is_ignored() {
+    let context = make_ctx();
+    let later = |millis: u64| Context {
+        simulated_time: context.simulated_time + Duration::from_millis(millis),
+        real_elapsed_time: context.real_elapsed_time + Duration::from_millis(millis),
+    };
+    let mut devices = DeviceManager::default();
+    super::set_up_peripherals(
+        &context,
+        &mut devices,
+        &super::PeripheralConfiguration::default(),
+    );
+    let (mut control, _mem) = setup(&context, Address::from(u18!(0o250)));
+    control
+        .set_alarm_masked(AlarmKind::MISAL, true)
+        .expect("MISAL should be maskable");
+    // Unit 41 is not connected, so MISAL is not routed to the
+    // program.
+    let datrac = u6!(0o50);
+    devices
+        .connect(
+            &context,
+            None,
+            &datrac,
+            Unsigned12Bit::ZERO,
+            &mut control.alarm_unit,
+        )
+        .expect("DATRAC should connect");
+    devices
+        .on_input_event(
+            &context,
+            datrac,
+            super::InputEvent::DatracSamples {
+                samples: vec![1, 2, 3],
+                sample_rate: 100,
+            },
+        )
+        .expect("DATRAC should accept samples");
+    devices.update_poll_time(&context, datrac);
+    // Nothing reads the samples, so by the time the unit is polled
+    // it has missed data.
+    match control.poll_hardware(&later(30), &mut devices, super::RunMode::InLimbo) {
+        Ok((run_mode, _)) => {
+            // The DATRAC flag is raised, but sequence 41's is not.
+            assert_eq!(run_mode, super::RunMode::Running);
+        }
+        Err(alarm) => panic!("masked MISAL should not stop the machine: {alarm}"),
+    }
+    assert!(!control.unmasked_alarm_active());
+    assert!(control.regs.flags.current_flag_state(&datrac));
+    assert!(!control.regs.flags.current_flag_state(&u6!(0o41)));
+}
+
+#[test]
+fn test_ios_sets_misc_output_word() {
+    const COMPLAIN: &str = "failed to set up instruction as test data";
+    let context = make_ctx();
+    let p = Address::from(u18!(0o250));
+    let mut devices = DeviceManager::default();
+    super::set_up_peripherals(
+        &context,
+        &mut devices,
+        &super::PeripheralConfiguration::default(),
+    );
+    let (mut control, mut mem) = setup(&context, p);
+    control.regs.k = Some(u6!(0o75));
+    control.regs.flags.lower_all();
+    control.regs.flags.raise(&u6!(0o75));
+    let data = Address::from(u18!(0o100));
+    let tsd = Instruction::from(&SymbolicInstruction {
+        held: false,
+        configuration: Unsigned5Bit::ZERO,
+        opcode: Opcode::Tsd,
+        index: Unsigned6Bit::ZERO,
+        operand_address: OperandAddress::Direct(data),
+    })
+    .bits();
+    for (address, word) in [
+        (p, ios_instruction(u6!(0o75), 0o34_001)),
+        (p.index_by(Signed18Bit::try_from(1).expect(COMPLAIN)), tsd),
+        (data, u36!(0o123_456_654_321)),
+    ] {
+        control
+            .memory_store_without_exchange(
+                &context,
+                &mut mem,
+                &address,
+                &word,
+                &UpdateE::No,
+                &MetaBitChange::None,
+            )
+            .expect(COMPLAIN);
+    }
+    let mut outputs = Vec::new();
+    for _ in 0..2 {
+        let mut poll_order_change: Option<SequenceNumber> = None;
+        match control.execute_instruction(&context, &mut devices, &mut mem, &mut poll_order_change)
+        {
+            Ok((_, _, output)) => outputs.extend(output),
+            Err((alarm, _)) => panic!("execution failed: {alarm}"),
+        }
+    }
+    // Connecting unit 75 sets the miscellaneous output word to the
+    // selected mode.
+    assert_eq!(
+        outputs,
+        vec![super::OutputEvent::MiscOutputWord { word: u36!(0o4001) }]
+    );
+    // Sequence 75 is a non-INOUT sequence, so TSD just cycles the
+    // target word.
+    match control.fetch_operand_from_address_without_exchange(
+        &context,
+        &mut mem,
+        &data,
+        &UpdateE::No,
+    ) {
+        Ok((word, _)) => assert_eq!(word, u36!(0o247_135_530_642)),
+        Err(alarm) => panic!("failed to read the TSD target: {alarm}"),
+    }
+}
+
+#[test]
+fn test_lda_reads_shaft_encoder() {
+    const COMPLAIN: &str = "failed to set up instruction as test data";
+    let context = make_ctx();
+    let p = Address::from(u18!(0o250));
+    let mut devices = DeviceManager::default();
+    let (mut control, mut mem) = setup(&context, p);
+    // Have the trap circuit raise the TRAP flag when an operand's
+    // metabit is set, so that we can see the metabit of the shaft
+    // encoder register.
+    control
+        .trap
+        .connect(&context, Unsigned12Bit::try_from(0o4_u16).expect(COMPLAIN));
+    let trap_seq = u6!(0o42);
+    let lda = Instruction::from(&SymbolicInstruction {
+        held: false,
+        configuration: Unsigned5Bit::ZERO,
+        opcode: Opcode::Lda,
+        index: Unsigned6Bit::ZERO,
+        operand_address: OperandAddress::Direct(Address::from(u18!(0o377620))),
+    })
+    .bits();
+    for offset in 0..2 {
+        control
+            .memory_store_without_exchange(
+                &context,
+                &mut mem,
+                &p.index_by(Signed18Bit::try_from(offset).expect(COMPLAIN)),
+                &lda,
+                &UpdateE::No,
+                &MetaBitChange::None,
+            )
+            .expect(COMPLAIN);
+    }
+    mem.set_shaft_encoder_knob(0, 0o123).expect(COMPLAIN);
+    // Positions wrap around at 512.
+    mem.set_shaft_encoder_knob(3, 0o1000 + 0o765)
+        .expect(COMPLAIN);
+    assert!(mem.set_shaft_encoder_knob(4, 0).is_err());
+
+    let mut execute = |control: &mut ControlUnit, mem: &mut MemoryUnit| {
+        let mut poll_order_change: Option<SequenceNumber> = None;
+        if let Err((alarm, _)) =
+            control.execute_instruction(&context, &mut devices, mem, &mut poll_order_change)
+        {
+            panic!("execution failed: {alarm}");
+        }
+    };
+
+    // The knobs have moved, so the metabit is set.
+    execute(&mut control, &mut mem);
+    assert_eq!(mem.get_a_register(), u36!(0o765_000_000_123));
+    assert!(control.current_flag_state(&trap_seq));
+
+    // Reading the register cleared the metabit.
+    control.regs.flags.lower(&trap_seq);
+    execute(&mut control, &mut mem);
+    assert_eq!(mem.get_a_register(), u36!(0o765_000_000_123));
+    assert!(!control.current_flag_state(&trap_seq));
+}
@@ -51,6 +51,10 @@ pub(crate) fn estimate_instruction_ns(
     };
     if let Ok(opcode) = Opcode::try_from(op) {
         tenths += match opcode {
+            // XEQ is undocumented, so we assume it costs about the
+            // same as JMP.  The time taken by the instruction it
+            // executes is not included.
+            Opcode::Xeq => 56,
             Opcode::Ios => 72,
             Opcode::Jmp => 56,
             Opcode::Jpx => 76,
@@ -97,9 +101,11 @@ pub(crate) fn estimate_instruction_ns(
             Opcode::Sca => 1060,
             Opcode::Scb => 1044,
             Opcode::Sab => 1072,
-            Opcode::Tly => 68,
-            Opcode::Div => 770,
-            Opcode::Mul => 100,
+            // The time taken by TLY, MUL and DIV also depends on
+            // the operands; see estimate_arithmetic_work_ns.
+            Opcode::Tly => 32,
+            Opcode::Div => 70,
+            Opcode::Mul => 30,
             Opcode::Sub => 68,
         }
     }
@@ -113,3 +119,67 @@ pub(crate) fn estimate_instruction_ns(
     // Convert from tenths of a microsecond to nanoseconds.
     tenths * 100
 }
+
+/// The work done by a TLY, MUL or DIV instruction, upon which the
+/// time it takes depends.  Subwords are processed in parallel, so these
+/// figures describe the subword which needed the most work.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ArithmeticWork {
+    /// The number of steps (for TLY, one per bit counted; for MUL
+    /// and DIV, one per magnitude bit of the subword).
+    pub(crate) steps: u32,
+    /// The number of those steps which needed an addition.
+    pub(crate) additions: u32,
+}
+
+/// Estimate the time taken by the data-dependent part of a TLY, MUL
+/// or DIV instruction, in addition to the time returned by
+/// [`estimate_instruction_ns`].
+///
+/// We model TLY as taking 0.1 microseconds per bit counted, MUL as
+/// taking 0.1 microseconds per step plus 0.1 microseconds per
+/// addition (that is, per one bit in the magnitude of the
+/// multiplier), and DIV as taking 2 microseconds per step.  So a
+/// full-word TLY takes 6.8 microseconds, a full-word MUL takes
+/// between 6.5 and 10 microseconds and a full-word DIV takes 77
+/// microseconds (each plus memory access time), but instructions
+/// operating on smaller subwords are quicker.  These rates are our
+/// own estimate (see OPEN-QUESTIONS.md).
+pub(crate) fn estimate_arithmetic_work_ns(opcode: Opcode, work: &ArithmeticWork) -> u64 {
+    // Units of tenths are tenths of microseconds
+    let tenths: u64 = match opcode {
+        Opcode::Tly => u64::from(work.steps),
+        Opcode::Mul => u64::from(work.steps) + u64::from(work.additions),
+        Opcode::Div => u64::from(work.steps) * 20,
+        _ => 0,
+    };
+    tenths * 100
+}
+
+#[test]
+fn test_estimate_arithmetic_work_ns() {
+    let full_word = ArithmeticWork {
+        steps: 35,
+        additions: 35,
+    };
+    let quarter = ArithmeticWork {
+        steps: 8,
+        additions: 1,
+    };
+    assert_eq!(estimate_arithmetic_work_ns(Opcode::Mul, &full_word), 7000);
+    assert_eq!(estimate_arithmetic_work_ns(Opcode::Mul, &quarter), 900);
+    assert_eq!(estimate_arithmetic_work_ns(Opcode::Div, &full_word), 70_000);
+    assert_eq!(estimate_arithmetic_work_ns(Opcode::Div, &quarter), 16_000);
+    assert_eq!(
+        estimate_arithmetic_work_ns(
+            Opcode::Tly,
+            &ArithmeticWork {
+                steps: 36,
+                additions: 0
+            }
+        ),
+        3600
+    );
+    assert_eq!(estimate_arithmetic_work_ns(Opcode::Tly, &quarter), 800);
+    assert_eq!(estimate_arithmetic_work_ns(Opcode::Add, &full_word), 0);
+}
@@ -6,7 +6,7 @@ use base::prelude::*;
 use std::time::Duration;
 
 use super::super::context::Context;
-use super::super::event::InputEvent;
+use super::super::event::{InputEvent, OutputEvent};
 use super::super::io::{InputFlagRaised, TransferFailed, Unit, UnitStatus};
 use super::super::*;
 
@@ -127,8 +127,9 @@ impl Unit for TrapCircuit {
         }
     }
 
-    fn connect(&mut self, _ctx: &Context, mode: Unsigned12Bit) {
+    fn connect(&mut self, _ctx: &Context, mode: Unsigned12Bit) -> Option<OutputEvent> {
         self.mode = mode;
+        None
     }
 
     fn transfer_mode(&self) -> TransferMode {
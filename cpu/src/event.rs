@@ -2,14 +2,37 @@ use std::error::Error;
 use std::fmt::{self, Display, Formatter};
 
 use base::charset::DescribedChar;
-use base::Unsigned6Bit;
+use base::{Unsigned36Bit, Unsigned6Bit};
 
 use super::alarm::Alarm;
 
 #[derive(Debug)]
 pub enum InputEvent {
-    PetrMountPaperTape { data: Vec<u8> },
-    LwKeyboardInput { data: Vec<Unsigned6Bit> },
+    PetrMountPaperTape {
+        data: Vec<u8>,
+    },
+    LwKeyboardInput {
+        data: Vec<Unsigned6Bit>,
+    },
+    /// The light pen has moved to (`x`, `y`), in display
+    /// coordinates.  `tracking` is false when the pen is not pointed
+    /// at the screen.
+    LightPenPosition {
+        x: u16,
+        y: u16,
+        tracking: bool,
+    },
+    /// Samples of the signal to be converted by the DATRAC A/D
+    /// converter (unit 50), taken at `sample_rate` samples per
+    /// second.
+    DatracSamples {
+        samples: Vec<i16>,
+        sample_rate: u32,
+    },
+    /// The miscellaneous input word (unit 47) has been set.
+    MiscInputWord {
+        word: Unsigned36Bit,
+    },
 }
 
 #[derive(Debug)]
@@ -61,4 +84,24 @@ pub enum OutputEvent {
         unit: Unsigned6Bit,
         ch: DescribedChar,
     },
+    /// A point has been plotted on the oscilloscope display (unit
+    /// 60).  The origin is at the bottom-left of the screen, and an
+    /// intensity of 0 is the brightest (7 is the dimmest).
+    DisplayPoint { x: u16, y: u16, intensity: u8 },
+    /// A line of paper tape has been punched (by unit 63).  The
+    /// value uses the same 7-channel format as the tape data read by
+    /// the paper tape reader.
+    PunchedLine { line: u8 },
+    /// A character has been printed by the Xerox printer (unit 51)
+    /// at the given position.  Pages, lines and columns are numbered
+    /// from zero.
+    XeroxPrint {
+        page: usize,
+        line: usize,
+        column: usize,
+        ch: DescribedChar,
+    },
+    /// The program has changed the miscellaneous output word (unit
+    /// 75).
+    MiscOutputWord { word: Unsigned36Bit },
 }
@@ -216,7 +216,7 @@ impl SystemConfiguration {
         result
     }
 
-    fn subword_form(&self) -> SubwordForm {
+    pub(crate) fn subword_form(&self) -> SubwordForm {
         const MASK: u16 = 0o3 << 7;
         match u16::from(self.0) & MASK {
             0b000000000 => SubwordForm::FullWord, // 36
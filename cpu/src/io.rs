@@ -16,7 +16,12 @@
 //! 0: Sequence which is run to start the computer (e.g. when "CODABO"
 //! or "START OVER" is pressed).
 //!
-//! 41: Handles various I/O alarm conditions.
+//! 41: Handles various I/O alarm conditions.  Connecting unit 41
+//! selects which alarms (bit 1 of the mode for IOSAL, bit 2 for
+//! MISAL) should raise the flag of sequence 41 instead of stopping
+//! the machine.  The "special" field of the unit 41 report word
+//! identifies the most recent such alarm (alarm code in 4.4-4.9,
+//! affected unit in 3.7-4.3).
 //! 42: Handles various trap conditions (see Users Handbook page 42).
 //! 47: Handles miscellaneous inputs
 //! 50: DATRAC (A/D converter)
@@ -50,16 +55,37 @@ use super::changelog::ChangeIndex;
 use super::context::Context;
 use super::event::*;
 use super::types::*;
-use super::PETR;
+use super::{
+    DATRAC, DISPLAY, INTERVAL_TIMER, LIGHT_PEN, MISC_INPUT, MISC_OUTPUT, PETR, PUNCH, RNG,
+    XEROX_PRINTER,
+};
 use base::charset::LincolnState;
 use base::prelude::*;
 
+mod dev_datrac;
+mod dev_display;
+mod dev_interval_timer;
+mod dev_light_pen;
 mod dev_lincoln_writer;
+mod dev_misc;
 mod dev_petr;
+mod dev_punch;
+mod dev_rng;
+mod dev_xerox;
 mod pollq;
 
+use dev_datrac::Datrac;
+use dev_display::OscilloscopeDisplay;
+pub use dev_display::{PhosphorFramebuffer, DISPLAY_SIZE};
+use dev_interval_timer::IntervalTimer;
+use dev_light_pen::LightPen;
 use dev_lincoln_writer::{LincolnWriterInput, LincolnWriterOutput};
+use dev_misc::{MiscInput, MiscOutput};
 pub(crate) use dev_petr::Petr;
+use dev_punch::Punch;
+use dev_rng::RandomNumberGenerator;
+pub use dev_rng::DEFAULT_RNG_SEED;
+use dev_xerox::XeroxPrinter;
 use pollq::PollQueue;
 
 /// When set, indicates that the controlling sequence has missed a data item.
@@ -167,6 +193,42 @@ fn make_unit_report_word(
     report | Unsigned36Bit::from(unit).shl(18) | Unsigned36Bit::from(status.special).shl(24)
 }
 
+/// Generate the report word for unit 41, the I/O alarm unit.  The
+/// special field contains the code of the most recent I/O alarm
+/// which was routed to the program (1 for IOSAL, 2 for MISAL) in the
+/// top 6 bits and the unit it affected in the bottom 6 bits.
+fn make_report_word_for_io_alarm_unit(alarm_unit: &AlarmUnit, current_flag: bool) -> Unsigned36Bit {
+    let (code, affected_unit): (u16, Unsigned6Bit) = match alarm_unit.program_alarm() {
+        Some(Alarm {
+            details: AlarmDetails::IOSAL { unit, .. },
+            ..
+        }) => (1, *unit),
+        Some(Alarm {
+            details: AlarmDetails::MISAL { affected_unit },
+            ..
+        }) => (2, *affected_unit),
+        _ => (0, Unsigned6Bit::ZERO),
+    };
+    let mode = alarm_unit.program_alarm_mode();
+    make_unit_report_word(
+        u6!(0o41),
+        mode != 0,
+        false, // not in maintenance
+        current_flag,
+        &UnitStatus {
+            special: Unsigned12Bit::try_from((code << 6) | u16::from(affected_unit))
+                .expect("alarm code and unit number should fit in 12 bits"),
+            change_flag: None,
+            buffer_available_to_cpu: false,
+            inability: false,
+            missed_data: false,
+            mode,
+            poll_after: Duration::from_secs(60),
+            is_input_unit: false,
+        },
+    )
+}
+
 fn make_report_word_for_invalid_unit(unit: Unsigned6Bit, current_flag: bool) -> Unsigned36Bit {
     make_unit_report_word(
         unit,
@@ -190,7 +252,8 @@ pub trait Unit {
     fn poll(&mut self, ctx: &Context) -> UnitStatus;
     /// Provide a text summary of the state of the device.
     fn text_info(&self, ctx: &Context) -> String;
-    fn connect(&mut self, ctx: &Context, mode: Unsigned12Bit);
+    /// Handle an IOS which connects the unit (selecting `mode`).
+    fn connect(&mut self, ctx: &Context, mode: Unsigned12Bit) -> Option<OutputEvent>;
     fn disconnect(&mut self, ctx: &Context);
     fn transfer_mode(&self) -> TransferMode;
     /// Handle a TSD on an input channel.
@@ -281,7 +344,7 @@ impl AttachedUnit {
         self.inner.borrow().text_info(ctx)
     }
 
-    pub fn connect(&self, ctx: &Context, mode: Unsigned12Bit) {
+    pub fn connect(&self, ctx: &Context, mode: Unsigned12Bit) -> Option<OutputEvent> {
         // It's permissible to call connect() on an attached but not connected unit.
         self.inner.borrow_mut().connect(ctx, mode)
     }
@@ -497,6 +560,9 @@ impl DeviceManager {
         current_flag: bool,
         alarm_unit: &mut AlarmUnit,
     ) -> Result<Unsigned36Bit, Alarm> {
+        if unit == u6!(0o41) {
+            return Ok(make_report_word_for_io_alarm_unit(alarm_unit, current_flag));
+        }
         match self.devices.get_mut(&unit) {
             Some(attached) => {
                 // Because the unit report word contains a `Connect`
@@ -660,6 +726,11 @@ impl DeviceManager {
         alarm_unit: &mut AlarmUnit,
     ) -> Result<(), Alarm> {
         let mut changed = false;
+        if *device == u6!(0o41) {
+            // Stop routing I/O alarms to the program.
+            alarm_unit.set_program_alarm_mode(Unsigned12Bit::ZERO);
+            return Ok(());
+        }
         if *device == u6!(0o42) {
             return Ok(());
         }
@@ -701,7 +772,7 @@ impl DeviceManager {
         device: &Unsigned6Bit,
         mode: Unsigned12Bit,
         alarm_unit: &mut AlarmUnit,
-    ) -> Result<Option<FlagChange>, Alarm> {
+    ) -> Result<(Option<FlagChange>, Option<OutputEvent>), Alarm> {
         self.mark_device_changed(*device);
         match self.devices.get_mut(device) {
             Some(attached) => {
@@ -720,9 +791,9 @@ impl DeviceManager {
                     } else {
                         None
                     };
-                    attached.connect(ctx, mode);
+                    let output = attached.connect(ctx, mode);
                     attached.connected = true;
-                    Ok(flag_change)
+                    Ok((flag_change, output))
                 }
             }
             None => {
@@ -734,7 +805,7 @@ impl DeviceManager {
                         message: format!("Attempt to connect missing unit {}", device),
                     },
                 })?;
-                Ok(None) // IOSAL is masked, carry on
+                Ok((None, None)) // IOSAL is masked, carry on
             }
         }
     }
@@ -772,7 +843,49 @@ impl Default for DeviceManager {
     }
 }
 
-pub fn set_up_peripherals(ctx: &Context, devices: &mut DeviceManager) {
+/// Configuration of the peripherals attached by
+/// [`set_up_peripherals`].  This is part of the configuration of the
+/// machine, so that (for example) a session run with the same
+/// configuration sees the same random numbers.
+#[derive(Debug, Clone)]
+pub struct PeripheralConfiguration {
+    /// Seed for the random number generator (unit 61).
+    pub rng_seed: u64,
+    /// The number of lines on each page printed by the Xerox printer
+    /// (unit 51).
+    pub xerox_lines_per_page: usize,
+    /// The number of characters which fit on a line printed by the
+    /// Xerox printer.  Longer lines are wrapped.
+    pub xerox_line_width: usize,
+}
+
+impl PeripheralConfiguration {
+    /// The size of the pages printed by the Xerox printer, as (lines
+    /// per page, line width).  A page always has room for at least
+    /// one character, whatever the configuration says.
+    pub fn xerox_page_size(&self) -> (usize, usize) {
+        (
+            self.xerox_lines_per_page.max(1),
+            self.xerox_line_width.max(1),
+        )
+    }
+}
+
+impl Default for PeripheralConfiguration {
+    fn default() -> PeripheralConfiguration {
+        PeripheralConfiguration {
+            rng_seed: DEFAULT_RNG_SEED,
+            xerox_lines_per_page: 66,
+            xerox_line_width: 80,
+        }
+    }
+}
+
+pub fn set_up_peripherals(
+    ctx: &Context,
+    devices: &mut DeviceManager,
+    config: &PeripheralConfiguration,
+) {
     const NOT_IN_MAINTENANCE: bool = false;
     fn attach_lw(
         ctx: &Context,
@@ -789,4 +902,52 @@ pub fn set_up_peripherals(ctx: &Context, devices: &mut DeviceManager) {
 
     devices.attach(ctx, PETR, NOT_IN_MAINTENANCE, Box::new(Petr::new()));
     attach_lw(ctx, u6!(0o65), u6!(0o66), devices);
+    // The second Lincoln Writer has its own (independent) state.
+    attach_lw(ctx, u6!(0o71), u6!(0o72), devices);
+    devices.attach(
+        ctx,
+        INTERVAL_TIMER,
+        NOT_IN_MAINTENANCE,
+        Box::new(IntervalTimer::new()),
+    );
+    // The light pen sees the points plotted on the display.
+    let screen = Rc::new(RefCell::new(PhosphorFramebuffer::new()));
+    devices.attach(
+        ctx,
+        DISPLAY,
+        NOT_IN_MAINTENANCE,
+        Box::new(OscilloscopeDisplay::new(screen.clone())),
+    );
+    devices.attach(
+        ctx,
+        LIGHT_PEN,
+        NOT_IN_MAINTENANCE,
+        Box::new(LightPen::new(screen)),
+    );
+    devices.attach(
+        ctx,
+        RNG,
+        NOT_IN_MAINTENANCE,
+        Box::new(RandomNumberGenerator::new(config.rng_seed)),
+    );
+    devices.attach(ctx, PUNCH, NOT_IN_MAINTENANCE, Box::new(Punch::new()));
+    devices.attach(ctx, DATRAC, NOT_IN_MAINTENANCE, Box::new(Datrac::new()));
+    devices.attach(
+        ctx,
+        MISC_INPUT,
+        NOT_IN_MAINTENANCE,
+        Box::new(MiscInput::new()),
+    );
+    devices.attach(
+        ctx,
+        MISC_OUTPUT,
+        NOT_IN_MAINTENANCE,
+        Box::new(MiscOutput::new()),
+    );
+    devices.attach(
+        ctx,
+        XEROX_PRINTER,
+        NOT_IN_MAINTENANCE,
+        Box::new(XeroxPrinter::new(config.xerox_page_size())),
+    );
 }
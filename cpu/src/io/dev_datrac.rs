@@ -0,0 +1,246 @@
+//! DATRAC analog-to-digital converter, unit 50
+//!
+//! The converter samples an analog signal at a fixed rate.  Here the
+//! signal is supplied by the user interface (using
+//! [`InputEvent::DatracSamples`]) as a sequence of 16-bit samples
+//! together with the rate at which they were taken.  Conversion
+//! begins when the unit is connected, and each sample becomes
+//! available one sample period after the previous one.
+//!
+//! A TSD on unit 50 reads the most recent sample as a signed (one's
+//! complement) 36-bit number.  If a new sample is converted before
+//! the program has read the previous one, the previous sample is
+//! lost and the converter reports missed data (so MISAL is raised).
+//! We don't know the resolution or the range of sample rates of the
+//! real converter; this is noted in OPEN-QUESTIONS.md.
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use base::prelude::*;
+use tracing::{event, Level};
+
+use super::super::context::Context;
+use super::super::event::{InputEvent, InputEventError, OutputEvent};
+use super::super::io::{FlagChange, InputFlagRaised, TransferFailed, Unit, UnitStatus};
+use super::super::types::*;
+
+const LATER: Duration = Duration::from_secs(300);
+
+#[derive(Debug)]
+pub(crate) struct Datrac {
+    mode: Unsigned12Bit,
+    connected: bool,
+    /// Samples which have not yet been converted.
+    samples: VecDeque<i16>,
+    sample_period: Duration,
+    /// When the next sample will be converted (None while the
+    /// converter is not running).
+    next_sample_at: Option<Duration>,
+    /// The most recently converted sample, if the program has not yet
+    /// read it.
+    latest: Option<i16>,
+    missed: bool,
+}
+
+impl Datrac {
+    pub(crate) fn new() -> Datrac {
+        Datrac {
+            mode: Unsigned12Bit::ZERO,
+            connected: false,
+            samples: VecDeque::new(),
+            sample_period: Duration::ZERO,
+            next_sample_at: None,
+            latest: None,
+            missed: false,
+        }
+    }
+
+    fn start(&mut self, now: Duration) {
+        self.next_sample_at = if self.connected && !self.samples.is_empty() {
+            Some(now + self.sample_period)
+        } else {
+            None
+        };
+    }
+
+    /// Convert the samples which are due by time `now`.
+    fn update(&mut self, now: Duration) {
+        while let Some(due) = self.next_sample_at {
+            if due > now {
+                break;
+            }
+            match self.samples.pop_front() {
+                Some(sample) => {
+                    if self.latest.is_some() {
+                        event!(
+                            Level::DEBUG,
+                            "DATRAC sample converted at {due:?} but the previous sample was not read"
+                        );
+                        self.missed = true;
+                    }
+                    self.latest = Some(sample);
+                }
+                None => {
+                    event!(Level::DEBUG, "DATRAC has run out of samples");
+                }
+            }
+            self.next_sample_at = if self.samples.is_empty() {
+                None
+            } else {
+                Some(due + self.sample_period)
+            };
+        }
+    }
+}
+
+impl Unit for Datrac {
+    fn poll(&mut self, ctx: &Context) -> UnitStatus {
+        self.update(ctx.simulated_time);
+        let ready = self.latest.is_some();
+        UnitStatus {
+            special: Unsigned12Bit::ZERO,
+            change_flag: if ready { Some(FlagChange::Raise) } else { None },
+            buffer_available_to_cpu: ready,
+            inability: false,
+            missed_data: self.missed,
+            mode: self.mode,
+            poll_after: self.next_sample_at.unwrap_or(ctx.simulated_time + LATER),
+            is_input_unit: true,
+        }
+    }
+
+    fn text_info(&self, _ctx: &Context) -> String {
+        if self.samples.is_empty() {
+            "No samples.".to_string()
+        } else {
+            format!(
+                "{} samples remaining, period {:?}.",
+                self.samples.len(),
+                self.sample_period
+            )
+        }
+    }
+
+    fn connect(&mut self, ctx: &Context, mode: Unsigned12Bit) -> Option<OutputEvent> {
+        event!(Level::INFO, "{} connected", self.name());
+        self.connected = true;
+        self.mode = mode;
+        self.latest = None;
+        self.missed = false;
+        self.start(ctx.simulated_time);
+        None
+    }
+
+    fn disconnect(&mut self, _ctx: &Context) {
+        self.connected = false;
+        self.next_sample_at = None;
+    }
+
+    fn transfer_mode(&self) -> TransferMode {
+        TransferMode::Exchange
+    }
+
+    fn read(&mut self, ctx: &Context) -> Result<MaskedWord, TransferFailed> {
+        self.update(ctx.simulated_time);
+        match self.latest.take() {
+            None => {
+                event!(Level::DEBUG, "no DATRAC sample is ready yet");
+                Err(TransferFailed::BufferNotFree)
+            }
+            Some(sample) => {
+                self.missed = false;
+                Ok(MaskedWord {
+                    bits: Signed36Bit::from(sample).reinterpret_as_unsigned(),
+                    mask: Unsigned36Bit::MAX,
+                })
+            }
+        }
+    }
+
+    fn write(
+        &mut self,
+        _ctx: &Context,
+        _source: Unsigned36Bit,
+    ) -> Result<Option<OutputEvent>, TransferFailed> {
+        unreachable!("attempted to write to an input device")
+    }
+
+    fn name(&self) -> String {
+        "DATRAC".to_string()
+    }
+
+    fn on_input_event(
+        &mut self,
+        ctx: &Context,
+        event: InputEvent,
+    ) -> Result<InputFlagRaised, InputEventError> {
+        if let InputEvent::DatracSamples {
+            samples,
+            sample_rate,
+        } = event
+        {
+            if sample_rate == 0 {
+                return Err(InputEventError::InputEventNotValidForDevice);
+            }
+            event!(
+                Level::INFO,
+                "DATRAC received {} samples at {sample_rate} samples per second",
+                samples.len()
+            );
+            self.samples = samples.into();
+            self.sample_period = Duration::from_secs(1) / sample_rate;
+            self.start(ctx.simulated_time);
+            // The flag is raised only when a sample has been
+            // converted.
+            Ok(InputFlagRaised::No)
+        } else {
+            Err(InputEventError::InputEventNotValidForDevice)
+        }
+    }
+}
+
+#[test]
+fn test_datrac_sampling() {
+    let at = |millis: u64| Context {
+        simulated_time: Duration::from_millis(millis),
+        real_elapsed_time: Duration::from_millis(millis),
+    };
+    let mut datrac = Datrac::new();
+    datrac.connect(&at(0), Unsigned12Bit::ZERO);
+    datrac
+        .on_input_event(
+            &at(0),
+            InputEvent::DatracSamples {
+                samples: vec![5, -3, 7, 9],
+                sample_rate: 100,
+            },
+        )
+        .expect("DATRAC should accept samples");
+
+    let status = datrac.poll(&at(5));
+    assert_eq!(status.change_flag, None);
+    assert_eq!(status.poll_after, Duration::from_millis(10));
+
+    assert_eq!(datrac.poll(&at(10)).change_flag, Some(FlagChange::Raise));
+    match datrac.read(&at(11)) {
+        Ok(word) => assert_eq!(word.bits, u36!(5)),
+        Err(e) => panic!("DATRAC read failed: {e:?}"),
+    }
+    match datrac.read(&at(20)) {
+        // Negative samples are in one's complement.
+        Ok(word) => assert_eq!(word.bits, u36!(0o777_777_777_774)),
+        Err(e) => panic!("DATRAC read failed: {e:?}"),
+    }
+
+    // The program is too slow to read the next sample.
+    let status = datrac.poll(&at(40));
+    assert!(status.missed_data);
+    match datrac.read(&at(41)) {
+        Ok(word) => assert_eq!(word.bits, u36!(9)),
+        Err(e) => panic!("DATRAC read failed: {e:?}"),
+    }
+    // There are no more samples.
+    let status = datrac.poll(&at(50));
+    assert_eq!(status.change_flag, None);
+    assert!(!status.missed_data);
+}
@@ -0,0 +1,346 @@
+//! Point-plotting oscilloscope display, unit 60
+//!
+//! Each TSD on unit 60 plots a single point.  We don't have a
+//! description of the word format used by the display hardware, so
+//! we use this layout (which is also noted in OPEN-QUESTIONS.md):
+//!
+//! | Intensity | Unused  | X coordinate | Unused  | Y coordinate |
+//! | --------- | ------- | ------------ | ------- | ------------ |
+//! | 4.7-4.9   | 4.2-4.6 | 3.1-4.1      | 2.2-2.9 | 1.1-2.1      |
+//! | (3 bits)  |         | (10 bits)    |         | (10 bits)    |
+//!
+//! The origin is at the bottom-left corner of the screen.  An
+//! intensity value of 0 is the brightest and 7 is the dimmest (so
+//! that a program which doesn't set the intensity bits gets a clearly
+//! visible point).
+//!
+//! The phosphor of the screen is simulated by [`PhosphorFramebuffer`],
+//! in which the brightness of each plotted point decays exponentially
+//! over (simulated) time.  The framebuffer is shared with the light
+//! pen (unit 55), which sees the points as they are plotted.
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::ops::Shl;
+use std::rc::Rc;
+use std::time::Duration;
+
+use base::prelude::*;
+use tracing::{event, Level};
+
+use super::super::context::Context;
+use super::super::event::{InputEvent, InputEventError, OutputEvent};
+use super::super::io::{FlagChange, InputFlagRaised, TransferFailed, Unit, UnitStatus};
+use super::super::types::*;
+use super::super::{Alarm, AlarmDetails, DISPLAY};
+
+/// The width and height of the display, in points.
+pub const DISPLAY_SIZE: u16 = 1024;
+
+/// How long it takes the display to plot a single point.  This is a
+/// guess.
+const POINT_PLOT_TIME: Duration = Duration::from_micros(30);
+
+/// The time taken for the brightness of a plotted point to fall by
+/// half.  This is a guess; the real display used a long-persistence
+/// phosphor.
+const PHOSPHOR_HALF_LIFE: Duration = Duration::from_millis(200);
+
+/// Points dimmer than this are treated as dark (and are forgotten).
+const VISIBILITY_THRESHOLD: f32 = 1.0 / 256.0;
+
+const LATER: Duration = Duration::from_secs(300);
+
+/// Extract the X coordinate, Y coordinate and intensity from a word
+/// written to the display.
+fn decode_point(word: Unsigned36Bit) -> (u16, u16, u8) {
+    let bits = u64::from(word);
+    let coord = |shift: u32| -> u16 {
+        u16::try_from((bits >> shift) & 0o1777).expect("coordinate should fit in 10 bits")
+    };
+    let intensity = u8::try_from((bits >> 33) & 0o7).expect("intensity should fit in 3 bits");
+    (coord(18), coord(0), intensity)
+}
+
+/// Generate a word containing the coordinates (`x`, `y`), in the
+/// same layout used for words written to the display.
+pub(crate) fn encode_point(x: u16, y: u16) -> Unsigned36Bit {
+    Unsigned36Bit::from(x & 0o1777).shl(18) | Unsigned36Bit::from(y & 0o1777)
+}
+
+/// The brightness (between 0 and 1) of a freshly plotted point of
+/// the given intensity (0 is brightest, 7 is dimmest).
+fn brightness_of_intensity(intensity: u8) -> f32 {
+    f32::from(8 - intensity.min(7)) / 8.0
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Glow {
+    brightness: f32,
+    when: Duration,
+}
+
+impl Glow {
+    fn brightness_at(&self, now: Duration) -> f32 {
+        let elapsed = now.saturating_sub(self.when);
+        let half_lives = elapsed.as_secs_f32() / PHOSPHOR_HALF_LIFE.as_secs_f32();
+        self.brightness * 0.5_f32.powf(half_lives)
+    }
+}
+
+/// Simulates the phosphor of the display screen.  Only the points
+/// which are still glowing are stored, since display programs
+/// generally plot only a small fraction of the screen.
+#[derive(Debug, Default)]
+pub struct PhosphorFramebuffer {
+    points: BTreeMap<(u16, u16), Glow>,
+}
+
+impl PhosphorFramebuffer {
+    pub fn new() -> PhosphorFramebuffer {
+        PhosphorFramebuffer::default()
+    }
+
+    /// Plot a point at (`x`, `y`) at (simulated) time `when`.  If
+    /// the point is still glowing from an earlier plot, the
+    /// brightness adds up (but saturates at 1).
+    pub fn plot(&mut self, x: u16, y: u16, intensity: u8, when: Duration) {
+        let remaining: f32 = self
+            .points
+            .get(&(x, y))
+            .map(|glow| glow.brightness_at(when))
+            .unwrap_or(0.0);
+        self.points.insert(
+            (x, y),
+            Glow {
+                brightness: (remaining + brightness_of_intensity(intensity)).min(1.0),
+                when,
+            },
+        );
+    }
+
+    /// Returns the brightness (between 0 and 1) of the point at
+    /// (`x`, `y`) at (simulated) time `now`.
+    pub fn brightness_at(&self, x: u16, y: u16, now: Duration) -> f32 {
+        self.points
+            .get(&(x, y))
+            .map(|glow| glow.brightness_at(now))
+            .unwrap_or(0.0)
+    }
+
+    /// Forget about points which have faded to invisibility by time
+    /// `now`.
+    pub fn prune(&mut self, now: Duration) {
+        self.points
+            .retain(|_, glow| glow.brightness_at(now) >= VISIBILITY_THRESHOLD);
+    }
+
+    /// Among the points plotted after time `after` which are still
+    /// visible at time `now`, find the earliest-plotted one which lies
+    /// within `aperture` points (in both X and Y) of (`x`, `y`).
+    pub(crate) fn first_point_plotted_near(
+        &self,
+        x: u16,
+        y: u16,
+        aperture: u16,
+        after: Duration,
+        now: Duration,
+    ) -> Option<(u16, u16)> {
+        self.points
+            .iter()
+            .filter(|((px, py), glow)| {
+                glow.when > after
+                    && px.abs_diff(x) <= aperture
+                    && py.abs_diff(y) <= aperture
+                    && glow.brightness_at(now) >= VISIBILITY_THRESHOLD
+            })
+            .min_by_key(|(_, glow)| glow.when)
+            .map(|(point, _)| *point)
+    }
+
+    /// Returns the number of points which are visible at time `now`.
+    pub fn visible_points(&self, now: Duration) -> usize {
+        self.points
+            .values()
+            .filter(|glow| glow.brightness_at(now) >= VISIBILITY_THRESHOLD)
+            .count()
+    }
+
+    /// Render the screen as seen at time `now`, as an 8-bit greyscale
+    /// image of [`DISPLAY_SIZE`] rows of [`DISPLAY_SIZE`] pixels.  The
+    /// top row of the screen comes first.
+    pub fn render(&self, now: Duration) -> Vec<u8> {
+        let size = usize::from(DISPLAY_SIZE);
+        let mut pixels: Vec<u8> = vec![0; size * size];
+        for (&(x, y), glow) in self.points.iter() {
+            let row = size - 1 - usize::from(y);
+            let level = (glow.brightness_at(now) * 255.0).round();
+            // The conversion cannot lose information since brightness
+            // is between 0 and 1.
+            pixels[row * size + usize::from(x)] = level as u8;
+        }
+        pixels
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct OscilloscopeDisplay {
+    mode: Unsigned12Bit,
+    connected: bool,
+    plot_will_be_finished_at: Option<Duration>,
+    screen: Rc<RefCell<PhosphorFramebuffer>>,
+}
+
+impl OscilloscopeDisplay {
+    pub(crate) fn new(screen: Rc<RefCell<PhosphorFramebuffer>>) -> OscilloscopeDisplay {
+        OscilloscopeDisplay {
+            mode: Unsigned12Bit::ZERO,
+            connected: false,
+            plot_will_be_finished_at: None,
+            screen,
+        }
+    }
+
+    fn is_busy(&self, now: Duration) -> bool {
+        matches!(self.plot_will_be_finished_at, Some(t) if t > now)
+    }
+}
+
+impl Unit for OscilloscopeDisplay {
+    fn poll(&mut self, ctx: &Context) -> UnitStatus {
+        let busy = self.is_busy(ctx.simulated_time);
+        let next_poll = match self.plot_will_be_finished_at {
+            Some(t) if busy => t,
+            _ => ctx.simulated_time + LATER,
+        };
+        UnitStatus {
+            special: Unsigned12Bit::ZERO,
+            change_flag: if self.connected && !busy {
+                Some(FlagChange::Raise)
+            } else {
+                None
+            },
+            buffer_available_to_cpu: !busy,
+            inability: false,
+            missed_data: false,
+            mode: self.mode,
+            poll_after: next_poll,
+            is_input_unit: false,
+        }
+    }
+
+    fn text_info(&self, ctx: &Context) -> String {
+        let activity = if self.is_busy(ctx.simulated_time) {
+            "Plotting"
+        } else {
+            "Idle"
+        };
+        match self.screen.try_borrow() {
+            Ok(screen) => format!(
+                "{}. {} points visible.",
+                activity,
+                screen.visible_points(ctx.simulated_time)
+            ),
+            Err(_) => format!("{activity}."),
+        }
+    }
+
+    fn connect(&mut self, _ctx: &Context, mode: Unsigned12Bit) -> Option<OutputEvent> {
+        event!(Level::INFO, "{} connected", self.name());
+        self.connected = true;
+        self.mode = mode;
+        None
+    }
+
+    fn disconnect(&mut self, _ctx: &Context) {
+        self.connected = false;
+    }
+
+    fn transfer_mode(&self) -> TransferMode {
+        TransferMode::Exchange
+    }
+
+    fn read(&mut self, _ctx: &Context) -> Result<MaskedWord, TransferFailed> {
+        unreachable!("attempted to read from an output device")
+    }
+
+    fn write(
+        &mut self,
+        ctx: &Context,
+        source: Unsigned36Bit,
+    ) -> Result<Option<OutputEvent>, TransferFailed> {
+        let now = ctx.simulated_time;
+        if self.is_busy(now) {
+            event!(Level::DEBUG, "cannot complete TSD, we are already plotting");
+            return Err(TransferFailed::BufferNotFree);
+        }
+        let (x, y, intensity) = decode_point(source);
+        event!(
+            Level::TRACE,
+            "plotting point ({x}, {y}) with intensity {intensity}"
+        );
+        match self.screen.try_borrow_mut() {
+            Ok(mut screen) => {
+                self.plot_will_be_finished_at = Some(now + POINT_PLOT_TIME);
+                screen.prune(now);
+                screen.plot(x, y, intensity, now);
+                Ok(Some(OutputEvent::DisplayPoint { x, y, intensity }))
+            }
+            Err(e) => Err(TransferFailed::Alarm(Alarm {
+                sequence: Some(DISPLAY),
+                details: AlarmDetails::BUGAL {
+                    instr: None,
+                    message: format!(
+                        "attempted to plot a point while the display is being examined by the light pen: {e}"
+                    ),
+                },
+            })),
+        }
+    }
+
+    fn name(&self) -> String {
+        "point-plotting oscilloscope display".to_string()
+    }
+
+    fn on_input_event(
+        &mut self,
+        _ctx: &Context,
+        _event: InputEvent,
+    ) -> Result<InputFlagRaised, InputEventError> {
+        Err(InputEventError::InputEventNotValidForDevice)
+    }
+}
+
+#[test]
+fn test_decode_point() {
+    assert_eq!(decode_point(u36!(0o000_001_000_002)), (1, 2, 0));
+    assert_eq!(decode_point(u36!(0o701_777_001_777)), (0o1777, 0o1777, 7));
+    // Bits outside the coordinate and intensity fields are ignored.
+    assert_eq!(decode_point(u36!(0o076_000_776_000)), (0, 0, 0));
+    assert_eq!(
+        decode_point(encode_point(0o1234, 0o567)),
+        (0o1234, 0o567, 0)
+    );
+}
+
+#[test]
+fn test_phosphor_decay() {
+    let mut screen = PhosphorFramebuffer::new();
+    let start = Duration::from_secs(1);
+    screen.plot(10, 20, 0, start);
+    assert_eq!(screen.brightness_at(10, 20, start), 1.0);
+    assert_eq!(screen.brightness_at(20, 10, start), 0.0);
+    let later = start + PHOSPHOR_HALF_LIFE;
+    assert!((screen.brightness_at(10, 20, later) - 0.5).abs() < 1.0e-6);
+    assert_eq!(screen.visible_points(later), 1);
+
+    let pixels = screen.render(later);
+    let size = usize::from(DISPLAY_SIZE);
+    assert_eq!(pixels[(size - 1 - 20) * size + 10], 128);
+    assert_eq!(pixels.iter().filter(|p| **p != 0).count(), 1);
+
+    // After long enough, the point fades away completely.
+    let much_later = start + Duration::from_secs(10);
+    assert_eq!(screen.visible_points(much_later), 0);
+    screen.prune(much_later);
+    assert_eq!(screen.brightness_at(10, 20, start), 0.0);
+}
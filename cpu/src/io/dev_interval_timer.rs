@@ -0,0 +1,200 @@
+//! Interval timer, unit 54
+//!
+//! The interval timer raises its flag each time an interval elapses.
+//! The connect mode gives the length of the interval in milliseconds
+//! (so the longest interval is 4.095 seconds).  A mode of zero stops
+//! the timer.  We don't know how the real interval timer was
+//! programmed; this is noted in OPEN-QUESTIONS.md.
+//!
+//! A TSD on unit 54 reads the number of intervals which have elapsed
+//! since the previous TSD (usually 1).  If an interval elapses before
+//! the program has read the previous one, the timer reports missed
+//! data (and so MISAL is raised).
+use std::time::Duration;
+
+use base::prelude::*;
+use tracing::{event, Level};
+
+use super::super::context::Context;
+use super::super::event::{InputEvent, InputEventError, OutputEvent};
+use super::super::io::{FlagChange, InputFlagRaised, TransferFailed, Unit, UnitStatus};
+use super::super::types::*;
+
+const LATER: Duration = Duration::from_secs(300);
+
+#[derive(Debug)]
+pub(crate) struct IntervalTimer {
+    mode: Unsigned12Bit,
+    connected: bool,
+    /// When the current interval will elapse (None if the timer is
+    /// stopped).
+    next_expiry: Option<Duration>,
+    /// The number of intervals which have elapsed but which the
+    /// program has not yet read.
+    elapsed_intervals: u32,
+    missed: bool,
+}
+
+impl IntervalTimer {
+    pub(crate) fn new() -> IntervalTimer {
+        IntervalTimer {
+            mode: Unsigned12Bit::ZERO,
+            connected: false,
+            next_expiry: None,
+            elapsed_intervals: 0,
+            missed: false,
+        }
+    }
+
+    fn interval(&self) -> Option<Duration> {
+        match u16::from(self.mode) {
+            0 => None,
+            millis => Some(Duration::from_millis(millis.into())),
+        }
+    }
+
+    /// Account for the intervals which have elapsed by time `now`.
+    fn update(&mut self, now: Duration) {
+        if let (Some(interval), Some(mut expiry)) = (self.interval(), self.next_expiry) {
+            while expiry <= now {
+                self.elapsed_intervals = self.elapsed_intervals.saturating_add(1);
+                if self.elapsed_intervals > 1 {
+                    event!(
+                        Level::DEBUG,
+                        "interval timer expired at {expiry:?} but the previous interval was not read"
+                    );
+                    self.missed = true;
+                }
+                expiry += interval;
+            }
+            self.next_expiry = Some(expiry);
+        }
+    }
+}
+
+impl Unit for IntervalTimer {
+    fn poll(&mut self, ctx: &Context) -> UnitStatus {
+        self.update(ctx.simulated_time);
+        let expired = self.elapsed_intervals > 0;
+        UnitStatus {
+            special: Unsigned12Bit::ZERO,
+            change_flag: if expired {
+                Some(FlagChange::Raise)
+            } else {
+                None
+            },
+            buffer_available_to_cpu: expired,
+            inability: false,
+            missed_data: self.missed,
+            mode: self.mode,
+            poll_after: self.next_expiry.unwrap_or(ctx.simulated_time + LATER),
+            is_input_unit: true,
+        }
+    }
+
+    fn text_info(&self, _ctx: &Context) -> String {
+        match self.interval() {
+            Some(interval) if self.connected => format!("Interval {interval:?}."),
+            _ => "Stopped.".to_string(),
+        }
+    }
+
+    fn connect(&mut self, ctx: &Context, mode: Unsigned12Bit) -> Option<OutputEvent> {
+        self.connected = true;
+        self.mode = mode;
+        self.elapsed_intervals = 0;
+        self.missed = false;
+        self.next_expiry = self
+            .interval()
+            .map(|interval| ctx.simulated_time + interval);
+        event!(
+            Level::INFO,
+            "{} connected, interval is {:?}",
+            self.name(),
+            self.interval()
+        );
+        None
+    }
+
+    fn disconnect(&mut self, _ctx: &Context) {
+        self.connected = false;
+        self.next_expiry = None;
+    }
+
+    fn transfer_mode(&self) -> TransferMode {
+        TransferMode::Exchange
+    }
+
+    fn read(&mut self, ctx: &Context) -> Result<MaskedWord, TransferFailed> {
+        self.update(ctx.simulated_time);
+        match std::mem::take(&mut self.elapsed_intervals) {
+            0 => {
+                event!(Level::DEBUG, "no interval has elapsed yet");
+                Err(TransferFailed::BufferNotFree)
+            }
+            n => {
+                self.missed = false;
+                Ok(MaskedWord {
+                    bits: Unsigned36Bit::from(n),
+                    mask: Unsigned36Bit::MAX,
+                })
+            }
+        }
+    }
+
+    fn write(
+        &mut self,
+        _ctx: &Context,
+        _source: Unsigned36Bit,
+    ) -> Result<Option<OutputEvent>, TransferFailed> {
+        unreachable!("attempted to write to an input device")
+    }
+
+    fn name(&self) -> String {
+        "interval timer".to_string()
+    }
+
+    fn on_input_event(
+        &mut self,
+        _ctx: &Context,
+        _event: InputEvent,
+    ) -> Result<InputFlagRaised, InputEventError> {
+        Err(InputEventError::InputEventNotValidForDevice)
+    }
+}
+
+#[test]
+fn test_interval_timer() {
+    let at = |millis: u64| Context {
+        simulated_time: Duration::from_millis(millis),
+        real_elapsed_time: Duration::from_millis(millis),
+    };
+    let mut timer = IntervalTimer::new();
+    timer.connect(
+        &at(100),
+        Unsigned12Bit::try_from(20_u16).expect("mode should be valid"),
+    );
+
+    let status = timer.poll(&at(110));
+    assert_eq!(status.change_flag, None);
+    assert_eq!(status.poll_after, Duration::from_millis(120));
+
+    let status = timer.poll(&at(120));
+    assert_eq!(status.change_flag, Some(FlagChange::Raise));
+    assert!(!status.missed_data);
+    match timer.read(&at(121)) {
+        Ok(word) => assert_eq!(word.bits, u36!(1)),
+        Err(e) => panic!("interval timer read failed: {e:?}"),
+    }
+
+    // Two intervals pass without the program reading the timer.
+    let status = timer.poll(&at(165));
+    assert_eq!(status.change_flag, Some(FlagChange::Raise));
+    assert!(status.missed_data);
+    assert_eq!(status.poll_after, Duration::from_millis(180));
+    match timer.read(&at(166)) {
+        Ok(word) => assert_eq!(word.bits, u36!(2)),
+        Err(e) => panic!("interval timer read failed: {e:?}"),
+    }
+    assert!(!timer.poll(&at(170)).missed_data);
+}
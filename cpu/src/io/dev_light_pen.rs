@@ -0,0 +1,310 @@
+//! Light pen, unit 55
+//!
+//! The light pen is held against the screen of the point-plotting
+//! display (unit 60).  When the display plots a point which is
+//! within the aperture of the pen, the light pen raises its flag.  A
+//! TSD on unit 55 then reads the coordinates of the point which was
+//! seen, in the same layout as the words written to the display (X
+//! in bits 3.1-4.1, Y in bits 1.1-2.1).
+//!
+//! The position of the pen is supplied by the user interface (using
+//! [`InputEvent::LightPenPosition`]).  While the pen is not tracking
+//! (for example because it is not pointed at the screen) it sees
+//! nothing.
+//!
+//! Bits 1.1-1.6 of the connect mode give the half-width of the pen's
+//! (square) aperture in display points.  If these bits are zero, the
+//! aperture is [`DEFAULT_APERTURE`] points.
+use std::cell::RefCell;
+use std::rc::Rc;
+use std::time::Duration;
+
+use base::prelude::*;
+use tracing::{event, Level};
+
+use super::super::context::Context;
+use super::super::event::{InputEvent, InputEventError, OutputEvent};
+use super::super::io::{FlagChange, InputFlagRaised, TransferFailed, Unit, UnitStatus};
+use super::super::types::*;
+use super::dev_display::{encode_point, PhosphorFramebuffer};
+
+/// The default half-width of the pen's aperture, in display points.
+const DEFAULT_APERTURE: u16 = 8;
+
+/// How often we look for newly plotted points while the pen is
+/// tracking.
+const PEN_SAMPLE_INTERVAL: Duration = Duration::from_micros(500);
+
+const LATER: Duration = Duration::from_secs(300);
+
+#[derive(Debug)]
+pub(crate) struct LightPen {
+    mode: Unsigned12Bit,
+    connected: bool,
+    x: u16,
+    y: u16,
+    tracking: bool,
+    /// Points plotted at or before this time have already been
+    /// considered.
+    last_sample_time: Duration,
+    /// The coordinates of a point which has been seen but not yet
+    /// read by the program.
+    data: Option<(u16, u16)>,
+    overrun: bool,
+    screen: Rc<RefCell<PhosphorFramebuffer>>,
+}
+
+impl LightPen {
+    pub(crate) fn new(screen: Rc<RefCell<PhosphorFramebuffer>>) -> LightPen {
+        LightPen {
+            mode: Unsigned12Bit::ZERO,
+            connected: false,
+            x: 0,
+            y: 0,
+            tracking: false,
+            last_sample_time: Duration::ZERO,
+            data: None,
+            overrun: false,
+            screen,
+        }
+    }
+
+    fn aperture(&self) -> u16 {
+        match u16::from(self.mode) & 0o77 {
+            0 => DEFAULT_APERTURE,
+            n => n,
+        }
+    }
+
+    /// Look for points plotted since we last looked.
+    fn sample(&mut self, now: Duration) {
+        let since = self.last_sample_time;
+        self.last_sample_time = now;
+        if !self.connected || !self.tracking {
+            return;
+        }
+        let seen = match self.screen.try_borrow() {
+            Ok(screen) => {
+                screen.first_point_plotted_near(self.x, self.y, self.aperture(), since, now)
+            }
+            Err(_) => {
+                // The display is plotting a point right now; we will
+                // see it next time.
+                self.last_sample_time = since;
+                return;
+            }
+        };
+        if let Some((x, y)) = seen {
+            event!(Level::DEBUG, "light pen sees point ({x}, {y})");
+            if self.data.is_some() {
+                self.overrun = true;
+            }
+            self.data = Some((x, y));
+        }
+    }
+}
+
+impl Unit for LightPen {
+    fn poll(&mut self, ctx: &Context) -> UnitStatus {
+        self.sample(ctx.simulated_time);
+        let data_ready = self.data.is_some();
+        UnitStatus {
+            special: Unsigned12Bit::ZERO,
+            change_flag: if data_ready {
+                Some(FlagChange::Raise)
+            } else {
+                None
+            },
+            buffer_available_to_cpu: data_ready,
+            inability: false,
+            missed_data: self.overrun,
+            mode: self.mode,
+            poll_after: ctx.simulated_time
+                + if self.connected && self.tracking {
+                    PEN_SAMPLE_INTERVAL
+                } else {
+                    LATER
+                },
+            is_input_unit: true,
+        }
+    }
+
+    fn text_info(&self, _ctx: &Context) -> String {
+        format!(
+            "Pen at ({}, {}), {}.",
+            self.x,
+            self.y,
+            if self.tracking {
+                "tracking"
+            } else {
+                "not tracking"
+            }
+        )
+    }
+
+    fn connect(&mut self, ctx: &Context, mode: Unsigned12Bit) -> Option<OutputEvent> {
+        self.connected = true;
+        self.mode = mode;
+        self.last_sample_time = ctx.simulated_time;
+        self.data = None;
+        self.overrun = false;
+        event!(
+            Level::INFO,
+            "{} connected, aperture is {} points",
+            self.name(),
+            self.aperture()
+        );
+        None
+    }
+
+    fn disconnect(&mut self, _ctx: &Context) {
+        self.connected = false;
+    }
+
+    fn transfer_mode(&self) -> TransferMode {
+        TransferMode::Exchange
+    }
+
+    fn read(&mut self, _ctx: &Context) -> Result<MaskedWord, TransferFailed> {
+        match self.data.take() {
+            None => {
+                event!(Level::DEBUG, "the light pen has not seen anything yet");
+                Err(TransferFailed::BufferNotFree)
+            }
+            Some((x, y)) => {
+                // The program has caught up, so stop reporting missed
+                // data.
+                self.overrun = false;
+                Ok(MaskedWord {
+                    bits: encode_point(x, y),
+                    mask: Unsigned36Bit::MAX,
+                })
+            }
+        }
+    }
+
+    fn write(
+        &mut self,
+        _ctx: &Context,
+        _source: Unsigned36Bit,
+    ) -> Result<Option<OutputEvent>, TransferFailed> {
+        unreachable!("attempted to write to an input device")
+    }
+
+    fn name(&self) -> String {
+        "light pen".to_string()
+    }
+
+    fn on_input_event(
+        &mut self,
+        _ctx: &Context,
+        event: InputEvent,
+    ) -> Result<InputFlagRaised, InputEventError> {
+        if let InputEvent::LightPenPosition { x, y, tracking } = event {
+            event!(
+                Level::TRACE,
+                "light pen moved to ({x}, {y}), tracking={tracking}"
+            );
+            self.x = x;
+            self.y = y;
+            self.tracking = tracking;
+            // The flag is raised only when the pen sees a point.
+            Ok(InputFlagRaised::No)
+        } else {
+            Err(InputEventError::InputEventNotValidForDevice)
+        }
+    }
+}
+
+#[test]
+fn test_light_pen_sees_nearby_points() {
+    let screen = Rc::new(RefCell::new(PhosphorFramebuffer::new()));
+    let mut pen = LightPen::new(screen.clone());
+    let at = |millis: u64| Context {
+        simulated_time: Duration::from_millis(millis),
+        real_elapsed_time: Duration::from_millis(millis),
+    };
+    pen.connect(&at(0), Unsigned12Bit::ZERO);
+    pen.on_input_event(
+        &at(0),
+        InputEvent::LightPenPosition {
+            x: 100,
+            y: 200,
+            tracking: true,
+        },
+    )
+    .expect("light pen should accept position input");
+
+    // A point far from the pen is not seen.
+    screen
+        .borrow_mut()
+        .plot(300, 200, 0, Duration::from_millis(1));
+    assert_eq!(pen.poll(&at(2)).change_flag, None);
+
+    // A point within the aperture is seen.
+    screen
+        .borrow_mut()
+        .plot(104, 195, 0, Duration::from_millis(3));
+    assert_eq!(pen.poll(&at(4)).change_flag, Some(FlagChange::Raise));
+    match pen.read(&at(4)) {
+        Ok(word) => assert_eq!(word.bits, encode_point(104, 195)),
+        Err(e) => panic!("light pen read failed: {e:?}"),
+    }
+    // The same point is not reported twice.
+    assert_eq!(pen.poll(&at(5)).change_flag, None);
+
+    // When the pen is not tracking, it sees nothing.
+    pen.on_input_event(
+        &at(5),
+        InputEvent::LightPenPosition {
+            x: 100,
+            y: 200,
+            tracking: false,
+        },
+    )
+    .expect("light pen should accept position input");
+    screen
+        .borrow_mut()
+        .plot(100, 200, 0, Duration::from_millis(6));
+    assert_eq!(pen.poll(&at(7)).change_flag, None);
+}
+
+#[test]
+fn test_light_pen_overrun_is_cleared_by_read() {
+    let screen = Rc::new(RefCell::new(PhosphorFramebuffer::new()));
+    let mut pen = LightPen::new(screen.clone());
+    let at = |millis: u64| Context {
+        simulated_time: Duration::from_millis(millis),
+        real_elapsed_time: Duration::from_millis(millis),
+    };
+    pen.connect(&at(0), Unsigned12Bit::ZERO);
+    pen.on_input_event(
+        &at(0),
+        InputEvent::LightPenPosition {
+            x: 100,
+            y: 200,
+            tracking: true,
+        },
+    )
+    .expect("light pen should accept position input");
+
+    // The pen sees two points, but the program doesn't read the
+    // first, so the unit reports missed data (which raises MISAL).
+    screen
+        .borrow_mut()
+        .plot(100, 200, 0, Duration::from_millis(1));
+    assert!(!pen.poll(&at(2)).missed_data);
+    screen
+        .borrow_mut()
+        .plot(101, 201, 0, Duration::from_millis(3));
+    assert!(pen.poll(&at(4)).missed_data);
+
+    // Reading the latest point clears the condition.
+    match pen.read(&at(5)) {
+        Ok(word) => assert_eq!(word.bits, encode_point(101, 201)),
+        Err(e) => panic!("light pen read failed: {e:?}"),
+    }
+    let status = pen.poll(&at(6));
+    assert!(!status.missed_data);
+    assert_eq!(status.change_flag, None);
+}
@@ -116,10 +116,11 @@ impl Unit for LincolnWriterOutput {
         }
     }
 
-    fn connect(&mut self, _ctx: &Context, mode: base::Unsigned12Bit) {
+    fn connect(&mut self, _ctx: &Context, mode: base::Unsigned12Bit) -> Option<OutputEvent> {
         event!(Level::INFO, "{} connected", self.name(),);
         self.connected = true;
         self.mode = mode;
+        None
     }
 
     fn read(&mut self, _ctx: &Context) -> Result<MaskedWord, TransferFailed> {
@@ -248,6 +249,9 @@ fn check_output(
         (None, Ok(Some(actual))) => {
             panic!("printing code {out:o} should have produced no output event, but actually produced {actual:?}");
         }
+        (Some(expected), Ok(Some(actual))) => {
+            panic!("printing code {out:o} should have produced {expected:?}, but actually produced {actual:?}");
+        }
         (_, Err(e)) => {
             panic!("output transfer failed {e:?}");
         }
@@ -450,9 +454,10 @@ impl Unit for LincolnWriterInput {
         .to_string()
     }
 
-    fn connect(&mut self, _ctx: &Context, mode: Unsigned12Bit) {
+    fn connect(&mut self, _ctx: &Context, mode: Unsigned12Bit) -> Option<OutputEvent> {
         self.mode = mode;
         self.connected = true;
+        None
     }
 
     fn disconnect(&mut self, _ctx: &Context) {
@@ -0,0 +1,264 @@
+//! Miscellaneous input (unit 47) and output (unit 75)
+//!
+//! These units have no particular peripheral attached; they are
+//! simply 36 bits of input and output.
+//!
+//! The miscellaneous input word behaves like a row of toggle
+//! switches.  The user interface sets it (using
+//! [`InputEvent::MiscInputWord`]) and it keeps its value until it is
+//! set again.  A TSD on unit 47 reads the current value of the word
+//! at any time, but the unit's flag is raised only when the value
+//! changes.
+//!
+//! The miscellaneous output word behaves like a row of indicator
+//! lamps.  The Users Handbook lists sequence 75 as a non-INOUT
+//! sequence, so a TSD on it doesn't reach the unit (see
+//! OPEN-QUESTIONS.md).  Instead, the program sets the lamps by
+//! connecting unit 75; the lamps show the (12-bit) mode selected by
+//! the IOS instruction.  Each change to their value is delivered to
+//! the user interface as an [`OutputEvent::MiscOutputWord`].
+use std::time::Duration;
+
+use base::prelude::*;
+use tracing::{event, Level};
+
+use super::super::context::Context;
+use super::super::event::{InputEvent, InputEventError, OutputEvent};
+use super::super::io::{FlagChange, InputFlagRaised, TransferFailed, Unit, UnitStatus};
+use super::super::types::*;
+
+const LATER: Duration = Duration::from_secs(300);
+
+#[derive(Debug)]
+pub(crate) struct MiscInput {
+    mode: Unsigned12Bit,
+    connected: bool,
+    word: Unsigned36Bit,
+    /// Set when the word has changed since the program last read it.
+    changed: bool,
+}
+
+impl MiscInput {
+    pub(crate) fn new() -> MiscInput {
+        MiscInput {
+            mode: Unsigned12Bit::ZERO,
+            connected: false,
+            word: Unsigned36Bit::ZERO,
+            changed: false,
+        }
+    }
+}
+
+impl Unit for MiscInput {
+    fn poll(&mut self, ctx: &Context) -> UnitStatus {
+        UnitStatus {
+            special: Unsigned12Bit::ZERO,
+            change_flag: if self.connected && self.changed {
+                Some(FlagChange::Raise)
+            } else {
+                None
+            },
+            buffer_available_to_cpu: self.connected,
+            inability: false,
+            missed_data: false,
+            mode: self.mode,
+            poll_after: ctx.simulated_time + LATER,
+            is_input_unit: true,
+        }
+    }
+
+    fn text_info(&self, _ctx: &Context) -> String {
+        format!("Input word {:012o}.", self.word)
+    }
+
+    fn connect(&mut self, _ctx: &Context, mode: Unsigned12Bit) -> Option<OutputEvent> {
+        event!(Level::INFO, "{} connected", self.name());
+        self.connected = true;
+        self.mode = mode;
+        None
+    }
+
+    fn disconnect(&mut self, _ctx: &Context) {
+        self.connected = false;
+    }
+
+    fn transfer_mode(&self) -> TransferMode {
+        TransferMode::Exchange
+    }
+
+    fn read(&mut self, _ctx: &Context) -> Result<MaskedWord, TransferFailed> {
+        self.changed = false;
+        Ok(MaskedWord {
+            bits: self.word,
+            mask: Unsigned36Bit::MAX,
+        })
+    }
+
+    fn write(
+        &mut self,
+        _ctx: &Context,
+        _source: Unsigned36Bit,
+    ) -> Result<Option<OutputEvent>, TransferFailed> {
+        unreachable!("attempted to write to an input device")
+    }
+
+    fn name(&self) -> String {
+        "miscellaneous input".to_string()
+    }
+
+    fn on_input_event(
+        &mut self,
+        _ctx: &Context,
+        event: InputEvent,
+    ) -> Result<InputFlagRaised, InputEventError> {
+        if let InputEvent::MiscInputWord { word } = event {
+            event!(Level::DEBUG, "miscellaneous input word set to {word:012o}");
+            if word == self.word {
+                return Ok(InputFlagRaised::No);
+            }
+            self.word = word;
+            self.changed = true;
+            Ok(if self.connected {
+                InputFlagRaised::Yes
+            } else {
+                InputFlagRaised::No
+            })
+        } else {
+            Err(InputEventError::InputEventNotValidForDevice)
+        }
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct MiscOutput {
+    mode: Unsigned12Bit,
+    connected: bool,
+    word: Unsigned36Bit,
+}
+
+impl MiscOutput {
+    pub(crate) fn new() -> MiscOutput {
+        MiscOutput {
+            mode: Unsigned12Bit::ZERO,
+            connected: false,
+            word: Unsigned36Bit::ZERO,
+        }
+    }
+}
+
+impl Unit for MiscOutput {
+    fn poll(&mut self, ctx: &Context) -> UnitStatus {
+        // The output word can be set at any time.
+        UnitStatus {
+            special: Unsigned12Bit::ZERO,
+            change_flag: if self.connected {
+                Some(FlagChange::Raise)
+            } else {
+                None
+            },
+            buffer_available_to_cpu: true,
+            inability: false,
+            missed_data: false,
+            mode: self.mode,
+            poll_after: ctx.simulated_time + LATER,
+            is_input_unit: false,
+        }
+    }
+
+    fn text_info(&self, _ctx: &Context) -> String {
+        format!("Output word {:012o}.", self.word)
+    }
+
+    fn connect(&mut self, _ctx: &Context, mode: Unsigned12Bit) -> Option<OutputEvent> {
+        event!(Level::INFO, "{} connected", self.name());
+        self.connected = true;
+        self.mode = mode;
+        let word = Unsigned36Bit::from(mode);
+        if word == self.word {
+            None
+        } else {
+            event!(
+                Level::DEBUG,
+                "miscellaneous output word changed from {:012o} to {:012o}",
+                self.word,
+                word
+            );
+            self.word = word;
+            Some(OutputEvent::MiscOutputWord { word })
+        }
+    }
+
+    fn disconnect(&mut self, _ctx: &Context) {
+        // The lamps keep their setting.
+        self.connected = false;
+    }
+
+    fn transfer_mode(&self) -> TransferMode {
+        TransferMode::Exchange
+    }
+
+    fn read(&mut self, _ctx: &Context) -> Result<MaskedWord, TransferFailed> {
+        unreachable!("attempted to read from an output device")
+    }
+
+    fn write(
+        &mut self,
+        _ctx: &Context,
+        _source: Unsigned36Bit,
+    ) -> Result<Option<OutputEvent>, TransferFailed> {
+        unreachable!("TSD on sequence 75 does not transfer data")
+    }
+
+    fn name(&self) -> String {
+        "miscellaneous output".to_string()
+    }
+
+    fn on_input_event(
+        &mut self,
+        _ctx: &Context,
+        _event: InputEvent,
+    ) -> Result<InputFlagRaised, InputEventError> {
+        Err(InputEventError::InputEventNotValidForDevice)
+    }
+}
+
+#[test]
+fn test_misc_input_and_output() {
+    let ctx = Context {
+        simulated_time: Duration::from_secs(1),
+        real_elapsed_time: Duration::from_secs(1),
+    };
+    let read_word = |input: &mut MiscInput| match input.read(&ctx) {
+        Ok(word) => word.bits,
+        Err(e) => panic!("miscellaneous input read failed: {e:?}"),
+    };
+
+    let mut input = MiscInput::new();
+    input.connect(&ctx, Unsigned12Bit::ZERO);
+    assert_eq!(input.poll(&ctx).change_flag, None);
+    assert!(matches!(
+        input.on_input_event(&ctx, InputEvent::MiscInputWord { word: u36!(0o17) }),
+        Ok(InputFlagRaised::Yes)
+    ));
+    assert_eq!(input.poll(&ctx).change_flag, Some(FlagChange::Raise));
+    assert_eq!(read_word(&mut input), u36!(0o17));
+    // The switches keep their setting, but the flag isn't raised
+    // again until they change.
+    assert_eq!(input.poll(&ctx).change_flag, None);
+    assert_eq!(read_word(&mut input), u36!(0o17));
+    assert!(matches!(
+        input.on_input_event(&ctx, InputEvent::MiscInputWord { word: u36!(0o17) }),
+        Ok(InputFlagRaised::No)
+    ));
+
+    let mode = Unsigned12Bit::try_from(0o4001_u16).expect("valid mode");
+    let mut output = MiscOutput::new();
+    // The lamps start out dark, so selecting mode 0 is not a change.
+    assert_eq!(output.connect(&ctx, Unsigned12Bit::ZERO), None);
+    assert_eq!(
+        output.connect(&ctx, mode),
+        Some(OutputEvent::MiscOutputWord { word: u36!(0o4001) })
+    );
+    // Selecting the same mode again is not a change.
+    assert_eq!(output.connect(&ctx, mode), None);
+}
@@ -344,7 +344,7 @@ impl Unit for Petr {
         }
     }
 
-    fn connect(&mut self, ctx: &Context, mode: Unsigned12Bit) {
+    fn connect(&mut self, ctx: &Context, mode: Unsigned12Bit) -> Option<OutputEvent> {
         let system_time = &ctx.simulated_time;
         self.connected_at_elapsed_time = Some(ctx.real_elapsed_time);
         self.connected_at_system_time = Some(ctx.simulated_time);
@@ -376,6 +376,7 @@ impl Unit for Petr {
             self.mode,
             self.time_of_next_read,
         );
+        None
     }
 
     fn transfer_mode(&self) -> TransferMode {
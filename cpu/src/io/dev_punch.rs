@@ -0,0 +1,190 @@
+//! Paper tape punch, unit 63
+//!
+//! The punch produces 7-channel tape in the same format as is read
+//! by the paper tape reader (see [`super::dev_petr`]): each TSD on
+//! unit 63 punches the lowest 7 bits of the word as one line of tape
+//! (bit 1.7 is the seventh channel).  Punched lines are delivered to
+//! the user interface as [`OutputEvent::PunchedLine`], so that it can
+//! write them to a file which the reader (or `tx2dis`) can read back.
+//!
+//! The punch operates at [`LINES_PER_SECOND`] lines per second.  A
+//! TSD issued while the previous line is still being punched is an
+//! overrun; the line is lost, and the punch reports missed data (so
+//! MISAL is raised).  Well-behaved programs wait for the flag of
+//! sequence 63 before punching each line.
+use std::time::Duration;
+
+use base::prelude::*;
+use tracing::{event, Level};
+
+use super::super::context::Context;
+use super::super::event::{InputEvent, InputEventError, OutputEvent};
+use super::super::io::{FlagChange, InputFlagRaised, TransferFailed, Unit, UnitStatus};
+use super::super::types::*;
+
+/// The speed of the punch.
+const LINES_PER_SECOND: u64 = 300;
+
+const LINE_PUNCH_TIME: Duration = Duration::from_micros(1_000_000 / LINES_PER_SECOND);
+
+const LATER: Duration = Duration::from_secs(300);
+
+#[derive(Debug)]
+pub(crate) struct Punch {
+    mode: Unsigned12Bit,
+    connected: bool,
+    punch_will_be_finished_at: Option<Duration>,
+    lines_punched: usize,
+    overrun: bool,
+}
+
+impl Punch {
+    pub(crate) fn new() -> Punch {
+        Punch {
+            mode: Unsigned12Bit::ZERO,
+            connected: false,
+            punch_will_be_finished_at: None,
+            lines_punched: 0,
+            overrun: false,
+        }
+    }
+
+    fn is_busy(&self, now: Duration) -> bool {
+        matches!(self.punch_will_be_finished_at, Some(t) if t > now)
+    }
+}
+
+impl Unit for Punch {
+    fn poll(&mut self, ctx: &Context) -> UnitStatus {
+        let busy = self.is_busy(ctx.simulated_time);
+        UnitStatus {
+            special: Unsigned12Bit::ZERO,
+            change_flag: if self.connected && !busy {
+                Some(FlagChange::Raise)
+            } else {
+                None
+            },
+            buffer_available_to_cpu: !busy,
+            inability: false,
+            missed_data: self.overrun,
+            mode: self.mode,
+            poll_after: match self.punch_will_be_finished_at {
+                Some(t) if busy => t,
+                _ => ctx.simulated_time + LATER,
+            },
+            is_input_unit: false,
+        }
+    }
+
+    fn text_info(&self, ctx: &Context) -> String {
+        format!(
+            "{}. {} lines punched.",
+            if self.is_busy(ctx.simulated_time) {
+                "Punching"
+            } else {
+                "Idle"
+            },
+            self.lines_punched
+        )
+    }
+
+    fn connect(&mut self, _ctx: &Context, mode: Unsigned12Bit) -> Option<OutputEvent> {
+        event!(Level::INFO, "{} connected", self.name());
+        self.connected = true;
+        self.mode = mode;
+        self.overrun = false;
+        None
+    }
+
+    fn disconnect(&mut self, _ctx: &Context) {
+        self.connected = false;
+    }
+
+    fn transfer_mode(&self) -> TransferMode {
+        TransferMode::Exchange
+    }
+
+    fn read(&mut self, _ctx: &Context) -> Result<MaskedWord, TransferFailed> {
+        unreachable!("attempted to read from an output device")
+    }
+
+    fn write(
+        &mut self,
+        ctx: &Context,
+        source: Unsigned36Bit,
+    ) -> Result<Option<OutputEvent>, TransferFailed> {
+        let now = ctx.simulated_time;
+        if self.is_busy(now) {
+            // The display (unit 60) and the Xerox printer (unit 51)
+            // refuse a TSD while they are busy (BufferNotFree, so the
+            // TSD waits).  The punch is different on purpose: it is
+            // required to model overrun, so the line is lost and
+            // MISAL is raised.
+            event!(
+                Level::WARN,
+                "punch overrun: line {:03o} was sent while the previous line was still being punched",
+                u64::from(source) & 0o177
+            );
+            self.overrun = true;
+            return Ok(None);
+        }
+        let line =
+            u8::try_from(u64::from(source) & 0o177).expect("a 7-bit value should fit in a u8");
+        event!(Level::DEBUG, "punching line {line:03o}");
+        self.punch_will_be_finished_at = Some(now + LINE_PUNCH_TIME);
+        self.lines_punched += 1;
+        // The missed data condition was reported when the punch was
+        // polled at the end of the previous line; now that the
+        // program is keeping up again, stop reporting it.
+        self.overrun = false;
+        Ok(Some(OutputEvent::PunchedLine { line }))
+    }
+
+    fn name(&self) -> String {
+        "paper tape punch".to_string()
+    }
+
+    fn on_input_event(
+        &mut self,
+        _ctx: &Context,
+        _event: InputEvent,
+    ) -> Result<InputFlagRaised, InputEventError> {
+        Err(InputEventError::InputEventNotValidForDevice)
+    }
+}
+
+#[test]
+fn test_punch_overrun() {
+    let at = |micros: u64| Context {
+        simulated_time: Duration::from_micros(micros),
+        real_elapsed_time: Duration::from_micros(micros),
+    };
+    let mut punch = Punch::new();
+    punch.connect(&at(0), Unsigned12Bit::ZERO);
+    assert_eq!(punch.poll(&at(0)).change_flag, Some(FlagChange::Raise));
+    assert!(matches!(
+        punch.write(&at(0), u36!(0o777_777_777_142)),
+        Ok(Some(OutputEvent::PunchedLine { line: 0o142 }))
+    ));
+    let status = punch.poll(&at(10));
+    assert_eq!(status.change_flag, None);
+    assert_eq!(status.poll_after, LINE_PUNCH_TIME);
+
+    // Punching another line too soon loses it.
+    assert!(matches!(punch.write(&at(20), u36!(0o100)), Ok(None)));
+    assert!(punch.poll(&at(30)).missed_data);
+
+    // Once the line has been punched, the punch is ready again.
+    let ready_at = u64::try_from(LINE_PUNCH_TIME.as_micros()).expect("punch time is short");
+    assert_eq!(
+        punch.poll(&at(ready_at)).change_flag,
+        Some(FlagChange::Raise)
+    );
+    assert!(punch.poll(&at(ready_at)).missed_data);
+    assert!(matches!(
+        punch.write(&at(ready_at), u36!(0o77)),
+        Ok(Some(OutputEvent::PunchedLine { line: 0o77 }))
+    ));
+    // A line punched successfully clears the overrun condition.
+    assert!(!punch.poll(&at(ready_at + 10)).missed_data);
+}
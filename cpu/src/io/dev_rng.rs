@@ -0,0 +1,156 @@
+//! Random number generator, unit 61
+//!
+//! Each TSD on unit 61 reads a 36-bit random word.  The words come
+//! from a deterministic pseudo-random generator whose seed is part
+//! of the [`PeripheralConfiguration`](super::PeripheralConfiguration),
+//! so that a session run with the same seed sees the same sequence
+//! of random numbers.  The user interface may choose to seed the
+//! generator from the host instead.
+use std::time::Duration;
+
+use base::prelude::*;
+use tracing::{event, Level};
+
+use super::super::context::Context;
+use super::super::event::{InputEvent, InputEventError, OutputEvent};
+use super::super::io::{FlagChange, InputFlagRaised, TransferFailed, Unit, UnitStatus};
+use super::super::types::*;
+
+const LATER: Duration = Duration::from_secs(300);
+
+/// The seed used unless the configuration specifies another one.
+pub const DEFAULT_RNG_SEED: u64 = 0o123_456_701_234;
+
+/// The SplitMix64 generator.  It's simple, fast and its output is
+/// good enough for the sort of programs the TX-2 would have run.
+#[derive(Debug)]
+struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+}
+
+#[derive(Debug)]
+pub(crate) struct RandomNumberGenerator {
+    mode: Unsigned12Bit,
+    connected: bool,
+    seed: u64,
+    generator: SplitMix64,
+}
+
+impl RandomNumberGenerator {
+    pub(crate) fn new(seed: u64) -> RandomNumberGenerator {
+        RandomNumberGenerator {
+            mode: Unsigned12Bit::ZERO,
+            connected: false,
+            seed,
+            generator: SplitMix64 { state: seed },
+        }
+    }
+
+    fn next_word(&mut self) -> Unsigned36Bit {
+        // The top bits of the SplitMix64 output are as good as any
+        // others.
+        Unsigned36Bit::try_from(self.generator.next() >> 28)
+            .expect("a 64-bit value shifted right by 28 bits should fit in 36 bits")
+    }
+}
+
+impl Unit for RandomNumberGenerator {
+    fn poll(&mut self, ctx: &Context) -> UnitStatus {
+        // A random number is always available.
+        UnitStatus {
+            special: Unsigned12Bit::ZERO,
+            change_flag: if self.connected {
+                Some(FlagChange::Raise)
+            } else {
+                None
+            },
+            buffer_available_to_cpu: self.connected,
+            inability: false,
+            missed_data: false,
+            mode: self.mode,
+            poll_after: ctx.simulated_time + LATER,
+            is_input_unit: true,
+        }
+    }
+
+    fn text_info(&self, _ctx: &Context) -> String {
+        format!("Seed {:o}.", self.seed)
+    }
+
+    fn connect(&mut self, _ctx: &Context, mode: Unsigned12Bit) -> Option<OutputEvent> {
+        event!(Level::INFO, "{} connected", self.name());
+        self.connected = true;
+        self.mode = mode;
+        None
+    }
+
+    fn disconnect(&mut self, _ctx: &Context) {
+        self.connected = false;
+    }
+
+    fn transfer_mode(&self) -> TransferMode {
+        TransferMode::Exchange
+    }
+
+    fn read(&mut self, _ctx: &Context) -> Result<MaskedWord, TransferFailed> {
+        let word = self.next_word();
+        event!(Level::TRACE, "random number is {word:o}");
+        Ok(MaskedWord {
+            bits: word,
+            mask: Unsigned36Bit::MAX,
+        })
+    }
+
+    fn write(
+        &mut self,
+        _ctx: &Context,
+        _source: Unsigned36Bit,
+    ) -> Result<Option<OutputEvent>, TransferFailed> {
+        unreachable!("attempted to write to an input device")
+    }
+
+    fn name(&self) -> String {
+        "random number generator".to_string()
+    }
+
+    fn on_input_event(
+        &mut self,
+        _ctx: &Context,
+        _event: InputEvent,
+    ) -> Result<InputFlagRaised, InputEventError> {
+        Err(InputEventError::InputEventNotValidForDevice)
+    }
+}
+
+#[test]
+fn test_rng_is_deterministic() {
+    let ctx = Context {
+        simulated_time: Duration::from_secs(1),
+        real_elapsed_time: Duration::from_secs(1),
+    };
+    let read_words = |seed: u64| -> Vec<Unsigned36Bit> {
+        let mut rng = RandomNumberGenerator::new(seed);
+        rng.connect(&ctx, Unsigned12Bit::ZERO);
+        (0..4)
+            .map(|_| match rng.read(&ctx) {
+                Ok(word) => word.bits,
+                Err(e) => panic!("RNG read failed: {e:?}"),
+            })
+            .collect()
+    };
+    let first = read_words(DEFAULT_RNG_SEED);
+    assert_eq!(first, read_words(DEFAULT_RNG_SEED));
+    assert_ne!(first, read_words(DEFAULT_RNG_SEED + 1));
+    // Successive words differ.
+    assert_ne!(first[0], first[1]);
+}
@@ -0,0 +1,294 @@
+//! Xerox printer, unit 51
+//!
+//! The printer accepts Lincoln Writer character codes (the lowest 6
+//! bits of each word written by a TSD on unit 51).  The codes are
+//! interpreted exactly as they are for the Lincoln Writer, so colour,
+//! case and script changes work in the same way.  We don't know the
+//! speed of the real printer or how it handled the end of a page;
+//! this is noted in OPEN-QUESTIONS.md.
+//!
+//! The printer keeps track of the position on the page of each
+//! character it prints, wrapping long lines and starting a new page
+//! when the current one is full.  The page size is part of the
+//! [`PeripheralConfiguration`](super::PeripheralConfiguration).  Each
+//! printed character is delivered to the user interface as an
+//! [`OutputEvent::XeroxPrint`] giving its position, so that the user
+//! interface can render the pages.
+use std::time::Duration;
+
+use base::charset::{
+    lincoln_char_to_described_char, DescribedChar, LincolnChar, LincolnState, LincolnStateTextInfo,
+};
+use base::prelude::*;
+use tracing::{event, Level};
+
+use super::super::context::Context;
+use super::super::event::{InputEvent, InputEventError, OutputEvent};
+use super::super::io::{FlagChange, InputFlagRaised, TransferFailed, Unit, UnitStatus};
+use super::super::types::*;
+
+/// The time taken to print each character.
+const CHAR_PRINT_TIME: Duration = Duration::from_millis(10);
+
+/// Tab stops are placed every `TAB_WIDTH` columns.
+const TAB_WIDTH: usize = 8;
+
+const LATER: Duration = Duration::from_secs(300);
+
+/// The position on the output at which the next character will be
+/// printed.  Pages, lines and columns are numbered from zero.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+struct PrintPosition {
+    page: usize,
+    line: usize,
+    column: usize,
+}
+
+#[derive(Debug)]
+pub(crate) struct XeroxPrinter {
+    mode: Unsigned12Bit,
+    connected: bool,
+    lines_per_page: usize,
+    line_width: usize,
+    print_will_be_finished_at: Option<Duration>,
+    state: LincolnState,
+    position: PrintPosition,
+}
+
+impl XeroxPrinter {
+    /// Create a printer whose pages have the size given by
+    /// [`PeripheralConfiguration::xerox_page_size`](super::PeripheralConfiguration::xerox_page_size).
+    pub(crate) fn new((lines_per_page, line_width): (usize, usize)) -> XeroxPrinter {
+        XeroxPrinter {
+            mode: Unsigned12Bit::ZERO,
+            connected: false,
+            lines_per_page,
+            line_width,
+            print_will_be_finished_at: None,
+            state: LincolnState::default(),
+            position: PrintPosition::default(),
+        }
+    }
+
+    fn is_busy(&self, now: Duration) -> bool {
+        matches!(self.print_will_be_finished_at, Some(t) if t > now)
+    }
+
+    fn line_feed(&mut self) {
+        self.position.line += 1;
+        if self.position.line >= self.lines_per_page {
+            self.position.page += 1;
+            self.position.line = 0;
+        }
+    }
+
+    /// Update the print position for `ch`, returning the position at
+    /// which `ch` should be printed (if it is printed at all).
+    fn place(&mut self, ch: &DescribedChar) -> Option<PrintPosition> {
+        match ch.base_char {
+            LincolnChar::Unprintable(_) => None,
+            LincolnChar::UnicodeBaseChar('\r') => {
+                // Carriage return also advances the paper.
+                self.position.column = 0;
+                self.line_feed();
+                None
+            }
+            LincolnChar::UnicodeBaseChar('\n') => {
+                self.line_feed();
+                None
+            }
+            LincolnChar::UnicodeBaseChar('\u{008D}') => {
+                // Reverse line feed (but not past the top of the page).
+                self.position.line = self.position.line.saturating_sub(1);
+                None
+            }
+            LincolnChar::UnicodeBaseChar('\t') => {
+                self.position.column = (self.position.column / TAB_WIDTH + 1) * TAB_WIDTH;
+                None
+            }
+            LincolnChar::UnicodeBaseChar('\u{0008}') => {
+                self.position.column = self.position.column.saturating_sub(1);
+                None
+            }
+            LincolnChar::UnicodeBaseChar(' ') => {
+                self.position.column += 1;
+                None
+            }
+            LincolnChar::UnicodeBaseChar(c) if c.is_control() => {
+                // Other control characters (for example DELETE, code
+                // 77) print nothing and don't move the print position.
+                None
+            }
+            LincolnChar::UnicodeBaseChar(_) => {
+                if self.position.column >= self.line_width {
+                    // Wrap long lines.
+                    self.position.column = 0;
+                    self.line_feed();
+                }
+                let here = self.position;
+                if ch.advance {
+                    self.position.column += 1;
+                }
+                Some(here)
+            }
+        }
+    }
+}
+
+impl Unit for XeroxPrinter {
+    fn poll(&mut self, ctx: &Context) -> UnitStatus {
+        let busy = self.is_busy(ctx.simulated_time);
+        UnitStatus {
+            special: Unsigned12Bit::ZERO,
+            change_flag: if self.connected && !busy {
+                Some(FlagChange::Raise)
+            } else {
+                None
+            },
+            buffer_available_to_cpu: !busy,
+            inability: false,
+            missed_data: false,
+            mode: self.mode,
+            poll_after: match self.print_will_be_finished_at {
+                Some(t) if busy => t,
+                _ => ctx.simulated_time + LATER,
+            },
+            is_input_unit: false,
+        }
+    }
+
+    fn text_info(&self, ctx: &Context) -> String {
+        let info: LincolnStateTextInfo = (&self.state).into();
+        format!(
+            "{}. Page {}, line {}. {}. {}. {}.",
+            if self.is_busy(ctx.simulated_time) {
+                "Printing"
+            } else {
+                "Idle"
+            },
+            self.position.page + 1,
+            self.position.line + 1,
+            info.script,
+            info.case,
+            info.colour
+        )
+    }
+
+    fn connect(&mut self, _ctx: &Context, mode: Unsigned12Bit) -> Option<OutputEvent> {
+        event!(Level::INFO, "{} connected", self.name());
+        self.connected = true;
+        self.mode = mode;
+        None
+    }
+
+    fn disconnect(&mut self, _ctx: &Context) {
+        self.connected = false;
+    }
+
+    fn transfer_mode(&self) -> TransferMode {
+        TransferMode::Exchange
+    }
+
+    fn read(&mut self, _ctx: &Context) -> Result<MaskedWord, TransferFailed> {
+        unreachable!("attempted to read from an output device")
+    }
+
+    fn write(
+        &mut self,
+        ctx: &Context,
+        source: Unsigned36Bit,
+    ) -> Result<Option<OutputEvent>, TransferFailed> {
+        if self.is_busy(ctx.simulated_time) {
+            event!(
+                Level::DEBUG,
+                "cannot complete TSD, the printer is still printing"
+            );
+            return Err(TransferFailed::BufferNotFree);
+        }
+        self.print_will_be_finished_at = Some(ctx.simulated_time + CHAR_PRINT_TIME);
+        let char_data = Unsigned6Bit::try_from(u64::from(source) & 0o77)
+            .expect("item should only have six value bits (this is a bug)");
+        let ch = match lincoln_char_to_described_char(char_data, &mut self.state) {
+            Some(ch) => ch,
+            None => {
+                return Ok(None);
+            }
+        };
+        Ok(self.place(&ch).map(
+            |PrintPosition { page, line, column }| OutputEvent::XeroxPrint {
+                page,
+                line,
+                column,
+                ch,
+            },
+        ))
+    }
+
+    fn name(&self) -> String {
+        "Xerox printer".to_string()
+    }
+
+    fn on_input_event(
+        &mut self,
+        _ctx: &Context,
+        _event: InputEvent,
+    ) -> Result<InputFlagRaised, InputEventError> {
+        Err(InputEventError::InputEventNotValidForDevice)
+    }
+}
+
+#[test]
+fn test_xerox_pagination() {
+    // Two lines of three characters per page.
+    let mut printer = XeroxPrinter::new((2, 3));
+    let mut now = Duration::from_secs(1);
+    printer.connect(
+        &Context {
+            simulated_time: now,
+            real_elapsed_time: now,
+        },
+        Unsigned12Bit::ZERO,
+    );
+    let mut print = |code: u8| -> Option<(usize, usize, usize, Option<char>)> {
+        now += CHAR_PRINT_TIME;
+        let ctx = Context {
+            simulated_time: now,
+            real_elapsed_time: now,
+        };
+        assert_eq!(printer.poll(&ctx).change_flag, Some(FlagChange::Raise));
+        match printer.write(&ctx, Unsigned36Bit::from(code)) {
+            Ok(Some(OutputEvent::XeroxPrint {
+                page,
+                line,
+                column,
+                ch,
+            })) => Some((page, line, column, ch.unicode_representation)),
+            Ok(None) => None,
+            Ok(Some(other)) => panic!("unexpected output event {other:?}"),
+            Err(e) => panic!("printer write failed: {e:?}"),
+        }
+    };
+    // Lower-case is the default, so these are digits.
+    assert_eq!(print(0o01), Some((0, 0, 0, Some('1'))));
+    assert_eq!(print(0o70), None); // space
+    assert_eq!(print(0o02), Some((0, 0, 2, Some('2'))));
+    // The line is full, so the next character wraps.
+    assert_eq!(print(0o03), Some((0, 1, 0, Some('3'))));
+    // Carriage return at the bottom of the page starts a new page.
+    assert_eq!(print(0o60), None);
+    assert_eq!(print(0o04), Some((1, 0, 0, Some('4'))));
+    // Delete prints nothing.
+    assert_eq!(print(0o77), None);
+    assert_eq!(print(0o05), Some((1, 0, 1, Some('5'))));
+
+    // The printer can't accept a character while it is printing.
+    let ctx = Context {
+        simulated_time: now,
+        real_elapsed_time: now,
+    };
+    assert_eq!(printer.poll(&ctx).change_flag, None);
+    assert!(matches!(
+        printer.write(&ctx, u36!(0o05)),
+        Err(TransferFailed::BufferNotFree)
+    ));
+}
@@ -24,9 +24,21 @@ pub use alarmunit::AlarmStatus;
 pub use context::Context;
 pub use control::{ControlRegisters, ControlUnit, PanicOnUnmaskedAlarm, ResetMode, RunMode};
 pub use event::*;
-pub use io::{set_up_peripherals, DeviceManager, ExtendedUnitState, InputFlagRaised};
-pub use memory::{MemoryConfiguration, MemoryUnit};
+pub use io::{
+    set_up_peripherals, DeviceManager, ExtendedUnitState, InputFlagRaised, PeripheralConfiguration,
+    PhosphorFramebuffer, DEFAULT_RNG_SEED, DISPLAY_SIZE,
+};
+pub use memory::{MemoryConfiguration, MemoryUnit, NoSuchShaftEncoderKnob, SHAFT_ENCODER_KNOBS};
 pub use tx2::Tx2;
 pub use types::*;
 
 pub const PETR: base::prelude::Unsigned6Bit = base::prelude::u6!(0o52);
+pub const LIGHT_PEN: base::prelude::Unsigned6Bit = base::prelude::u6!(0o55);
+pub const DATRAC: base::prelude::Unsigned6Bit = base::prelude::u6!(0o50);
+pub const MISC_INPUT: base::prelude::Unsigned6Bit = base::prelude::u6!(0o47);
+pub const MISC_OUTPUT: base::prelude::Unsigned6Bit = base::prelude::u6!(0o75);
+pub const DISPLAY: base::prelude::Unsigned6Bit = base::prelude::u6!(0o60);
+pub const INTERVAL_TIMER: base::prelude::Unsigned6Bit = base::prelude::u6!(0o54);
+pub const RNG: base::prelude::Unsigned6Bit = base::prelude::u6!(0o61);
+pub const PUNCH: base::prelude::Unsigned6Bit = base::prelude::u6!(0o63);
+pub const XEROX_PRINTER: base::prelude::Unsigned6Bit = base::prelude::u6!(0o51);
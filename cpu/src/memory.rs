@@ -75,6 +75,71 @@ impl Display for MemoryOpFailure {
 
 impl error::Error for MemoryOpFailure {}
 
+/// The number of knobs attached to the shaft encoder register.
+pub const SHAFT_ENCODER_KNOBS: usize = 4;
+
+/// Returned when the user interface tries to set the position of a
+/// shaft encoder knob which does not exist.  (TX-2 programs can only
+/// read the knob positions.)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NoSuchShaftEncoderKnob(pub usize);
+
+impl Display for NoSuchShaftEncoderKnob {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        write!(
+            f,
+            "there is no shaft encoder knob {} (knobs are numbered 0 to {})",
+            self.0,
+            SHAFT_ENCODER_KNOBS - 1
+        )
+    }
+}
+
+impl error::Error for NoSuchShaftEncoderKnob {}
+
+/// The shaft encoder register (V-memory location 377620).
+///
+/// We don't know how many knobs the real shaft encoder had, or how
+/// their positions were arranged within the register; this is noted
+/// in OPEN-QUESTIONS.md.  We assume four knobs, each of which reads
+/// as a 9-bit position in one quarter of the word (knob 0 in quarter
+/// 1, knob 3 in quarter 4).  The knobs turn continuously, so each
+/// position wraps around at 512.
+///
+/// The hardware sets the register's metabit when any knob moves.  The
+/// metabit stays set until the program next reads the register.
+#[derive(Debug, Default)]
+struct ShaftEncoder {
+    word: Unsigned36Bit,
+    moved: bool,
+}
+
+impl ShaftEncoder {
+    const POSITION_MASK: u64 = 0o777;
+
+    fn set_knob(&mut self, knob: usize, position: u16) -> Result<(), NoSuchShaftEncoderKnob> {
+        if knob >= SHAFT_ENCODER_KNOBS {
+            return Err(NoSuchShaftEncoderKnob(knob));
+        }
+        let shift = 9 * knob;
+        let word = u64::from(self.word);
+        let current = (word >> shift) & Self::POSITION_MASK;
+        let position = u64::from(position) & Self::POSITION_MASK;
+        if current != position {
+            event!(
+                Level::DEBUG,
+                "shaft encoder knob {knob} moved from {current:o} to {position:o}"
+            );
+            self.word = Unsigned36Bit::try_from(
+                (word & !(Self::POSITION_MASK << shift)) | (position << shift),
+            )
+            .expect("shaft encoder positions should fit in 36 bits");
+            self.moved = true;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub(crate) enum MetaBitChange {
     None,
@@ -428,6 +493,20 @@ impl MemoryUnit {
         self.v_memory.set_e_register(value);
     }
 
+    /// Set the value of the external input register.
+    pub fn set_external_input_register(&mut self, value: Unsigned36Bit) {
+        self.v_memory.external_input_register = value;
+    }
+
+    /// Turn shaft encoder knob `knob` to `position`.
+    pub fn set_shaft_encoder_knob(
+        &mut self,
+        knob: usize,
+        position: u16,
+    ) -> Result<(), NoSuchShaftEncoderKnob> {
+        self.v_memory.shaft_encoder.set_knob(knob, position)
+    }
+
     /// Perform a memory read access.  Return a MemoryReadRef for the
     /// memory word being accessed.
     fn read_access<'a>(
@@ -715,7 +794,8 @@ struct VMemory {
     //
     // V memory in general does behave as if it has a meta bit.  For
     // example, there is a push-button on the console that acts as the
-    // value of the meta bit of the shaft encoder register.
+    // value of the meta bit of the shaft encoder register.  We set
+    // that metabit when a knob moves (see [`ShaftEncoder`]).
     //
     // See also https://github.com/TX-2/TX-2-simulator/issues/59.
     a_register: Unsigned36Bit,
@@ -725,8 +805,10 @@ struct VMemory {
     e_register: Unsigned36Bit,
     m_register_metabit: bool,
 
-    unimplemented_shaft_encoder: MemoryWord,
-    unimplemented_external_input_register: MemoryWord,
+    shaft_encoder: ShaftEncoder,
+    /// Set by equipment outside the TX-2 (in our case, by the user
+    /// interface).
+    external_input_register: Unsigned36Bit,
     rtc: MemoryWord,
     rtc_start: Duration,
     codabo_start_point: [Unsigned36Bit; 8],
@@ -839,8 +921,8 @@ impl VMemory {
                 Unsigned36Bit::default(),
             ],
             plugboard: standard_plugboard_internal(),
-            unimplemented_shaft_encoder: MemoryWord::default(),
-            unimplemented_external_input_register: MemoryWord::default(),
+            shaft_encoder: ShaftEncoder::default(),
+            external_input_register: Unsigned36Bit::ZERO,
             rtc: MemoryWord::default(),
             rtc_start: ctx.real_elapsed_time,
             permit_unknown_reads: true,
@@ -929,25 +1011,21 @@ impl VMemory {
                 &mut self.m_register_metabit,
             )),
             0o0377620 => {
-                event!(
-                    Level::WARN,
-                    "Reading the shaft encoder is not yet implemented"
-                );
-                Ok(MemoryReadRef::readonly_from(
-                    &mut self.unimplemented_shaft_encoder,
-                ))
-            }
-            0o0377621 => {
-                event!(
-                    Level::WARN,
-                    "Reading the external input register is not yet implemented"
-                );
+                // Reading the register clears the metabit, so that
+                // the program can tell whether a knob has moved since
+                // it last looked.
+                let moved = std::mem::take(&mut self.shaft_encoder.moved);
                 Ok(readonly(
-                    &self.unimplemented_external_input_register.word,
-                    self.unimplemented_external_input_register.meta,
+                    &self.shaft_encoder.word,
+                    moved,
                     &mut self.sacrificial_metabit,
                 ))
             }
+            0o0377621 => Ok(readonly(
+                &self.external_input_register,
+                false,
+                &mut self.sacrificial_metabit,
+            )),
             0o0377630 => {
                 self.update_rtc(ctx);
                 Ok(MemoryReadRef::readonly_from(&mut self.rtc))
@@ -1240,3 +1318,25 @@ fn test_ae_registers_share_metabit() {
         }
     }
 }
+
+#[test]
+fn test_external_input_register() {
+    let context = make_ctx();
+    let mut mem = MemoryUnit::new(
+        &context,
+        &MemoryConfiguration {
+            with_u_memory: false,
+        },
+    );
+    let addr: Address = Address::from(u18!(0o0377621));
+    mem.set_external_input_register(u36!(0o123_456_765_432));
+    match mem.fetch(&context, &addr, &MetaBitChange::None) {
+        Ok((word, extra)) => {
+            assert_eq!(word, u36!(0o123_456_765_432));
+            assert!(!extra.meta);
+        }
+        Err(e) => panic!("failed to read the external input register: {e}"),
+    }
+    // The program cannot change the register.
+    assert!(matches!(mem.write_access(&context, &addr), Ok(None)));
+}
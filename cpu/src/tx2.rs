@@ -13,10 +13,12 @@ use super::alarmunit::AlarmStatus;
 use super::context::Context;
 use super::control::{ConfigurationMemorySetup, ControlUnit, ResetMode, RunMode};
 use super::event::{InputEvent, OutputEvent};
-use super::io::{set_up_peripherals, DeviceManager, ExtendedUnitState, InputFlagRaised};
-use super::memory::{MemoryConfiguration, MemoryUnit};
-use super::PETR;
+use super::io::{
+    set_up_peripherals, DeviceManager, ExtendedUnitState, InputFlagRaised, PeripheralConfiguration,
+};
+use super::memory::{MemoryConfiguration, MemoryUnit, NoSuchShaftEncoderKnob};
 use super::{InputEventError, PanicOnUnmaskedAlarm};
+use super::{DATRAC, LIGHT_PEN, MISC_INPUT, PETR};
 
 #[wasm_bindgen]
 pub struct Tx2 {
@@ -33,6 +35,7 @@ impl Tx2 {
         ctx: &Context,
         panic_on_unmasked_alarm: PanicOnUnmaskedAlarm,
         mem_config: &MemoryConfiguration,
+        peripheral_config: &PeripheralConfiguration,
     ) -> Tx2 {
         let control = ControlUnit::new(
             panic_on_unmasked_alarm,
@@ -46,7 +49,7 @@ impl Tx2 {
 
         let mem = MemoryUnit::new(ctx, mem_config);
         let mut devices = DeviceManager::new();
-        set_up_peripherals(ctx, &mut devices);
+        set_up_peripherals(ctx, &mut devices, peripheral_config);
         Tx2 {
             control,
             mem,
@@ -69,6 +72,12 @@ impl Tx2 {
         self.control.set_alarm_masked(kind, masked)
     }
 
+    /// Enable or disable experimental support for the XEQ
+    /// instruction (opcode 2).  It is disabled by default.
+    pub fn set_xeq_enabled(&mut self, enable: bool) {
+        self.control.set_xeq_enabled(enable)
+    }
+
     pub fn set_run_mode(&mut self, run_mode: RunMode) {
         self.run_mode = run_mode;
     }
@@ -128,6 +137,44 @@ impl Tx2 {
         self.on_input_event(ctx, PETR, InputEvent::PetrMountPaperTape { data })
     }
 
+    /// Supply the signal to be sampled by the DATRAC A/D converter,
+    /// replacing any samples which have not yet been converted.
+    pub fn datrac_samples(
+        &mut self,
+        ctx: &Context,
+        samples: Vec<i16>,
+        sample_rate: u32,
+    ) -> Result<InputFlagRaised, InputEventError> {
+        let result = self.on_input_event(
+            ctx,
+            DATRAC,
+            InputEvent::DatracSamples {
+                samples,
+                sample_rate,
+            },
+        )?;
+        // The converter needs to be polled when its first sample is
+        // due.
+        self.devices.update_poll_time(ctx, DATRAC);
+        self.next_hw_poll_due = min(self.next_hw_poll_due, ctx.simulated_time);
+        Ok(result)
+    }
+
+    /// Set the miscellaneous input word (unit 47), as if by setting
+    /// a row of toggle switches.  The unit's flag is raised if the
+    /// value changes.
+    pub fn set_misc_input(
+        &mut self,
+        ctx: &Context,
+        word: Unsigned36Bit,
+    ) -> Result<InputFlagRaised, InputEventError> {
+        let result = self.on_input_event(ctx, MISC_INPUT, InputEvent::MiscInputWord { word })?;
+        if let InputFlagRaised::Yes = result {
+            self.next_hw_poll_due = min(self.next_hw_poll_due, ctx.simulated_time);
+        }
+        Ok(result)
+    }
+
     pub fn lw_input(
         &mut self,
         ctx: &Context,
@@ -144,6 +191,46 @@ impl Tx2 {
         }
     }
 
+    /// Set the value of the external input register (V-memory
+    /// location 377621), as equipment wired into the TX-2 would.
+    pub fn set_external_input_register(&mut self, word: Unsigned36Bit) {
+        event!(Level::DEBUG, "external input register set to {word:012o}");
+        self.mem.set_external_input_register(word);
+    }
+
+    /// Turn shaft encoder knob `knob` to `position` (positions wrap
+    /// around at 512).  If the knob moves, the metabit of the shaft
+    /// encoder register is set.
+    pub fn set_shaft_encoder_knob(
+        &mut self,
+        knob: usize,
+        position: u16,
+    ) -> Result<(), NoSuchShaftEncoderKnob> {
+        self.mem.set_shaft_encoder_knob(knob, position)
+    }
+
+    /// Move the light pen to (`x`, `y`) (in display coordinates).
+    /// While `tracking` is false, the pen sees nothing.
+    pub fn light_pen_position(
+        &mut self,
+        ctx: &Context,
+        x: u16,
+        y: u16,
+        tracking: bool,
+    ) -> Result<InputFlagRaised, InputEventError> {
+        let result = self.on_input_event(
+            ctx,
+            LIGHT_PEN,
+            InputEvent::LightPenPosition { x, y, tracking },
+        )?;
+        // The pen only raises its flag when it sees a point, but it
+        // needs to be polled promptly to notice points plotted near
+        // its new position.
+        self.devices.update_poll_time(ctx, LIGHT_PEN);
+        self.next_hw_poll_due = min(self.next_hw_poll_due, ctx.simulated_time);
+        Ok(result)
+    }
+
     pub fn next_tick(&self) -> Duration {
         match (
             self.run_mode,
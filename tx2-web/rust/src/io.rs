@@ -7,7 +7,7 @@ use serde::Serialize;
 use tracing::{event, Level};
 use wasm_bindgen::prelude::*;
 
-use base::Unsigned6Bit;
+use base::{Unsigned36Bit, Unsigned6Bit};
 use cpu::*;
 
 use super::context::make_context;
@@ -35,6 +35,70 @@ pub fn tx2_load_tape(
     )
 }
 
+/// Move the light pen, for example in response to mouse movement.
+/// Returns true if the light pen raised its flag.
+#[wasm_bindgen]
+pub fn tx2_light_pen_position(
+    tx2: &mut Tx2,
+    simulated_time: f64,
+    elapsed_time_secs: f64,
+    x: u16,
+    y: u16,
+    tracking: bool,
+) -> bool {
+    let context = make_context(simulated_time, elapsed_time_secs);
+    tx2.light_pen_position(&context, x, y, tracking)
+        .map_or_else(
+            |e: InputEventError| {
+                event!(Level::ERROR, "failed to move light pen: {e}");
+                false
+            },
+            |f: InputFlagRaised| f.into(),
+        )
+}
+
+/// Set the miscellaneous input word (unit 47), as if by setting a
+/// row of toggle switches.  Returns true if the unit raised its flag.
+#[wasm_bindgen]
+pub fn tx2_set_misc_input(
+    tx2: &mut Tx2,
+    simulated_time: f64,
+    elapsed_time_secs: f64,
+    word: u64,
+) -> bool {
+    let context = make_context(simulated_time, elapsed_time_secs);
+    let word = match Unsigned36Bit::try_from(word) {
+        Ok(word) => word,
+        Err(e) => {
+            event!(
+                Level::ERROR,
+                "invalid miscellaneous input word {word:o}: {e}"
+            );
+            return false;
+        }
+    };
+    tx2.set_misc_input(&context, word).map_or_else(
+        |e: InputEventError| {
+            event!(Level::ERROR, "failed to set miscellaneous input: {e}");
+            false
+        },
+        |f: InputFlagRaised| f.into(),
+    )
+}
+
+/// Turn one of the shaft encoder knobs.  Returns false if there is
+/// no such knob.
+#[wasm_bindgen]
+pub fn tx2_set_shaft_encoder_knob(tx2: &mut Tx2, knob: usize, position: u16) -> bool {
+    tx2.set_shaft_encoder_knob(knob, position).map_or_else(
+        |e: NoSuchShaftEncoderKnob| {
+            event!(Level::ERROR, "failed to turn shaft encoder knob: {e}");
+            false
+        },
+        |()| true,
+    )
+}
+
 pub(crate) struct EmittedCodes {
     first: u8,
     second: Option<u8>,
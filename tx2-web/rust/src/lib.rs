@@ -62,7 +62,14 @@ pub fn create_tx2(simulated_system_time_secs: f64, elapsed_time_secs: f64) -> Tx
     };
     let context = make_context(simulated_system_time_secs, elapsed_time_secs);
     let panic_on_unmasked_alarm = cpu::PanicOnUnmaskedAlarm::No;
-    Tx2::new(&context, panic_on_unmasked_alarm, &mem_config)
+    // The default peripheral configuration uses a fixed seed for the
+    // random number generator, so runs are reproducible.
+    Tx2::new(
+        &context,
+        panic_on_unmasked_alarm,
+        &mem_config,
+        &PeripheralConfiguration::default(),
+    )
 }
 
 #[wasm_bindgen]
@@ -95,6 +102,33 @@ fn display_output_event(output_event: OutputEvent) {
             let doc: Document = document();
             display_lw_unit_output_event(unit, ch, doc)
         }
+        OutputEvent::DisplayPoint { x, y, intensity } => {
+            // The web UI doesn't have a display panel yet.
+            event!(
+                Level::TRACE,
+                "not showing display point ({x}, {y}) with intensity {intensity}"
+            );
+        }
+        OutputEvent::PunchedLine { line } => {
+            // The web UI doesn't have a paper tape punch panel yet.
+            event!(Level::TRACE, "discarding punched tape line {line:03o}");
+        }
+        OutputEvent::XeroxPrint {
+            page,
+            line,
+            column,
+            ch,
+        } => {
+            // The web UI doesn't have a printer panel yet.
+            event!(
+                Level::TRACE,
+                "not showing printed character {ch:?} at page {page}, line {line}, column {column}"
+            );
+        }
+        OutputEvent::MiscOutputWord { word } => {
+            // The web UI doesn't have indicator lamps yet.
+            event!(Level::TRACE, "miscellaneous output word is now {word:012o}");
+        }
     }
 }
 
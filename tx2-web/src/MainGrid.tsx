@@ -81,7 +81,7 @@ export const MainGrid = (props: MainGridProps) => (
     <Grid
       gap="10px"
       columns="1fr 9fr"
-      rows="auto auto 40ex"
+      rows="auto auto 40ex 40ex"
     >
       <Box column="1 / span 3" row="1" style={{overflowY: "scroll"}}>
         <Instructions /></Box>
@@ -110,6 +110,12 @@ export const MainGrid = (props: MainGridProps) => (
           outputUnit={0o66}
           tx2Controller={props.tx2Controller} />
       </Box>
+      <Box column="2" row="4" style={{padding: "20px", overflowY: "scroll"}}>
+        <LincolnWriter
+          inputUnit={0o71}
+          outputUnit={0o72}
+          tx2Controller={props.tx2Controller} />
+      </Box>
     </Grid>
   </div>
 );
@@ -1,4 +1,4 @@
-import { create_tx2, get_builtin_sample_tape, Tx2, tx2_codabo, tx2_device_statuses, tx2_do_tick, tx2_drain_device_changes, tx2_load_tape, tx2_lw_keyboard_click, tx2_next_simulated_tick, tx2_unmasked_alarm_active } from '../../build/tx2_web';
+import { create_tx2, get_builtin_sample_tape, Tx2, tx2_codabo, tx2_device_statuses, tx2_do_tick, tx2_drain_device_changes, tx2_light_pen_position, tx2_load_tape, tx2_lw_keyboard_click, tx2_next_simulated_tick, tx2_set_misc_input, tx2_set_shaft_encoder_knob, tx2_unmasked_alarm_active } from '../../build/tx2_web';
 import { AlarmController } from './alarms'
 import { IoController } from './io'
 import { WasmUnitState } from './types'
@@ -67,6 +67,31 @@ export class Tx2Controller {
         return result;
     }
 
+    // Move the light pen (for example, to follow the mouse over the
+    // display).  x and y are display coordinates, with the origin at
+    // the bottom-left.
+    lightPenMove(x: number, y: number, tracking: boolean): void {
+        const flag_raised = tx2_light_pen_position(this.tx2, this.systemTime, this.clamped_elapsed_seconds(), x, y, tracking);
+        if (flag_raised && this.running) {
+            this.tickSoon();
+        }
+    }
+
+    // Set the miscellaneous input word (unit 47), as if by setting a
+    // row of 36 toggle switches.
+    setMiscInput(word: bigint): void {
+        const flag_raised = tx2_set_misc_input(this.tx2, this.systemTime, this.clamped_elapsed_seconds(), word);
+        if (flag_raised && this.running) {
+            this.tickSoon();
+        }
+    }
+
+    setShaftEncoderKnob(knob: number, position: number): void {
+        // The program notices the movement when it next reads the
+        // shaft encoder register, so there is no flag to raise.
+        tx2_set_shaft_encoder_knob(this.tx2, knob, position);
+    }
+
     tick_after(interval: number, system_time_then: number): void {
         const delay_ms = interval * 1000.0;
         setTimeout(this.do_tick.bind(this), delay_ms, system_time_then);